@@ -1,8 +1,9 @@
+use std::env;
 use std::io;
 use std::io::{Write};
 use std::convert::TryInto;
 
-use monopoly::{game};
+use monopoly::{card, edition, game, rules, save};
 
 /// Get the number of players
 fn get_player_num(user_input: String) -> Result<i32, ()> {
@@ -42,6 +43,45 @@ fn capture_names(player_num: i32) -> Vec<String> {
     players
 }
 
+/// Get the way the game ends
+fn get_game_mode(user_input: String) -> Result<game::GameMode, ()> {
+    let mut parts = user_input.split_whitespace();
+    let mode = parts.next().ok_or(())?;
+    let limit = match parts.next() {
+        Some(l) => l.parse::<u32>().map_err(|_| ())?,
+        None    => 0
+    };
+    match mode {
+        "1" => Ok(game::GameMode::Classic),
+        "2" if limit > 0 => Ok(game::GameMode::TurnLimit(limit)),
+        "3" if limit > 0 => Ok(game::GameMode::TimeLimit(limit as u64)),
+        _ => Err(())
+    }
+}
+
+/// Capture the way the game ends
+fn capture_game_mode() -> game::GameMode {
+    println!("1. Play until one player remains");
+    println!("2 <turns>. Play a number of turns (eg 2 40)");
+    println!("3 <minutes>. Play a number of minutes (eg 3 90)");
+    loop {
+        print!("How does the game end? ");
+        let _= io::stdout().flush();
+        let mut user_input = String::new();
+        io::stdin().read_line(&mut user_input).expect("Did not enter a valid option");
+        user_input.pop(); // Remove newline
+
+        match get_game_mode(user_input) {
+            Ok(mode) => {
+                return mode;
+            },
+            Err(_) => {
+                println!("Enter a valid option");
+            }
+        };
+    }
+}
+
 /// Capture the amount of players
 fn capture_player_num() -> i32 {
     loop {
@@ -62,15 +102,70 @@ fn capture_player_num() -> i32 {
     }
 }
 
-fn main() {
+/// Board, cards and house rules of a new game
+#[derive(Debug)]
+struct Options {
+    edition: edition::Edition,
+    decks: card::Decks,
+    rules: rules::Rules
+}
+
+/// Get the options of a new game, like --board uk --cards my_cards.json --rules family
+// The board is the name of an edition (us or uk), or a file describing the board.
+// The rules are official, classic, family or quick.
+fn get_options(args: &[String]) -> Result<Options, String> {
+    let mut options = Options {
+        edition: edition::Edition::us(),
+        decks: card::Decks::classic(),
+        rules: rules::Rules::official()
+    };
+    for option in args.chunks(2) {
+        match option {
+            [name, board] if name == "--board" => {
+                options.edition = match edition::Edition::named(board) {
+                    Some(e) => e,
+                    None    => edition::Edition::load_from_file(board)?
+                };
+            },
+            [name, path] if name == "--cards" => {
+                options.decks = card::Decks::load_from_file(path)?;
+            },
+            [name, preset] if name == "--rules" => {
+                options.rules = rules::Rules::named(preset)
+                    .ok_or(format!("Unknown rules {}", preset))?;
+            },
+            _ => return Err(format!("Unknown option {}", option.join(" ")))
+        }
+    }
+    options.decks.fit_board(options.edition.squares.len())?;
+    Ok(options)
+}
+
+/// Start a new game
+fn new_game(options: Options) -> Result<game::Game, String> {
     let player_num = capture_player_num();
     let players = capture_names(player_num);
-    let game = game::init(players);
-    game.start();
+    game::init_with_edition(players, capture_game_mode(), options.rules, rand::random(),
+                            options.edition, options.decks)
+}
+
+fn main() {
+    // Continue a saved game, if a saved game is passed in (eg /tmp/monopoly.json),
+    // or play on another board, with other cards or house rules (eg --board uk --rules quick)
+    let args: Vec<String> = env::args().skip(1).collect();
+    let game = match args.as_slice() {
+        [path] => save::load_from_file(path),
+        options => get_options(options).and_then(new_game)
+    };
+    match game {
+        Ok(g)  => g.start(),
+        Err(e) => println!("{}", e)
+    };
 }
 
 #[cfg(test)]
 mod tests {
+    use super::*;
 
     #[test]
     fn test_player_num() {
@@ -81,4 +176,26 @@ mod tests {
         assert_eq!(get_player_num("8".to_string()), Ok(8));
         assert_eq!(get_player_num("9".to_string()), Err(()));
     }
+
+    #[test]
+    fn test_game_mode() {
+        assert_eq!(get_game_mode("1".to_string()), Ok(game::GameMode::Classic));
+        assert_eq!(get_game_mode("2 40".to_string()), Ok(game::GameMode::TurnLimit(40)));
+        assert_eq!(get_game_mode("3 90".to_string()), Ok(game::GameMode::TimeLimit(90)));
+        assert_eq!(get_game_mode("2".to_string()), Err(()));
+        assert_eq!(get_game_mode("3 0".to_string()), Err(()));
+        assert_eq!(get_game_mode("4 1".to_string()), Err(()));
+    }
+
+    #[test]
+    fn test_options() {
+        let args = |s: &str| s.split_whitespace().map(String::from).collect::<Vec<String>>();
+        assert_eq!(get_options(&args("")).unwrap().edition.name, "United States");
+        assert_eq!(get_options(&args("--board uk")).unwrap().edition.name, "United Kingdom");
+        assert_eq!(get_options(&args("--rules quick")).unwrap().rules, rules::Rules::quick());
+        assert_eq!(get_options(&args("--rules silly")).err().unwrap(), "Unknown rules silly");
+        assert!(get_options(&args("--board /nonexistent.json")).is_err());
+        assert_eq!(get_options(&args("--dice 6")).err().unwrap(), "Unknown option --dice 6");
+        assert_eq!(get_options(&args("--board")).err().unwrap(), "Unknown option --board");
+    }
 }
@@ -5,11 +5,13 @@ use serde::{Deserialize, Serialize};
 use log::{info};
 
 use std::sync::Mutex;
-use monopoly::{game};
+use monopoly::{game, rules};
+use monopoly::command::GameCommand;
+use monopoly::event::GameEvent;
+use monopoly::trade::{Trade, TradeOffer};
 
 struct AppState {
-    num: Mutex<u32>,
-//    game: Mutex<game::Game>
+    game: Mutex<game::Game>
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -18,6 +20,30 @@ struct DiceRoll {
     dice2: u32
 }
 
+#[derive(Serialize, Deserialize, Debug)]
+struct BidAmount {
+    amount: u32
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+struct TaxChoice {
+    net_worth: bool
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+struct CounterOffer {
+    gives: TradeOffer,
+    receives: TradeOffer
+}
+
+/// Respond with the events of a command
+fn events_response(events: Vec<GameEvent>) -> HttpResponse {
+    let response = serde_json::to_string(&events).unwrap();
+    HttpResponse::Ok()
+        .content_type("application/json; charset=utf-8")
+        .body(response)
+}
+
 #[get("/ping")]
 async fn ping() -> String {
     format!("pong")
@@ -25,45 +51,203 @@ async fn ping() -> String {
 
 #[post("/roll-dice")]
 /// Roll the dice in an established game.
-// Expects a request like: 
+// Expects a request like:
 // curl -X POST -H "Content-type: application/json" \
-//      -d '{"dice_roll": {"dice1": 3, "dice2": 2}}' \
+//      -d '{"dice1": 3, "dice2": 2}' \
 //      http://127.0.0.1:8000/roll-dice
 async fn roll_dice(app_state: web::Data<AppState>,
                    dice_roll: web::Json<DiceRoll>) -> HttpResponse {
     info!("Got {:?}", dice_roll);
 
-//    let dice = game::Dice::new(dice_roll.dice1, dice_roll.dice2);
-//    let g = app_state.game.lock().unwrap();
-//    info!("Active player: {:?}", g.active_player());
-//    g.go(dice);
-//    info!("Active player: {:?}", g.active_player());
-//
-    let mut n = app_state.num.lock().unwrap();
-    info!("num b4: {}", n);
-    *n += 1;
-    info!("num aftr: {}", n);
+    let mut g = app_state.game.lock().unwrap();
+    info!("Active player: {:?}", g.active_player());
+    let events = g.apply(GameCommand::RollDice(dice_roll.dice1, dice_roll.dice2));
+    info!("Active player: {:?}", g.active_player());
     // TODO: pass back all game information
-    let dice = DiceRoll { dice1: *n, dice2: 2};
-    let response = format!("{}",
-                           serde_json::to_string(&dice).unwrap());
+    events_response(events)
+}
+
+#[post("/command")]
+/// Apply any command to an established game, like buying a street or ending the turn.
+// Expects a request like:
+// curl -X POST -H "Content-type: application/json" \
+//      -d '{"BuyHouse": {"street": 1}}' \
+//      http://127.0.0.1:8000/command
+async fn command(app_state: web::Data<AppState>,
+                 command: web::Json<GameCommand>) -> HttpResponse {
+    info!("Got {:?}", command);
+    let mut g = app_state.game.lock().unwrap();
+    events_response(g.apply(command.into_inner()))
+}
+
+#[post("/undo")]
+/// Take back the last command in an established game.
+// Expects a request like:
+// curl -X POST http://127.0.0.1:8000/undo
+async fn undo(app_state: web::Data<AppState>) -> HttpResponse {
+    info!("Undo");
+    let mut g = app_state.game.lock().unwrap();
+    events_response(g.apply(GameCommand::Undo))
+}
+
+#[post("/redo")]
+/// Execute the last command that was taken back again.
+// Expects a request like:
+// curl -X POST http://127.0.0.1:8000/redo
+async fn redo(app_state: web::Data<AppState>) -> HttpResponse {
+    info!("Redo");
+    let mut g = app_state.game.lock().unwrap();
+    events_response(g.apply(GameCommand::Redo))
+}
+
+#[post("/end-game")]
+/// End an established game, as agreed by all players.
+// Expects a request like:
+// curl -X POST http://127.0.0.1:8000/end-game
+async fn end_game(app_state: web::Data<AppState>) -> HttpResponse {
+    info!("End game");
+    let mut g = app_state.game.lock().unwrap();
+    events_response(g.apply(GameCommand::EndGame))
+}
+
+#[post("/decline-purchase")]
+/// Don't buy the street the active player landed on, and put it on auction.
+// Expects a request like:
+// curl -X POST http://127.0.0.1:8000/decline-purchase
+async fn decline_purchase(app_state: web::Data<AppState>) -> HttpResponse {
+    let mut g = app_state.game.lock().unwrap();
+    events_response(g.apply(GameCommand::DeclinePurchase))
+}
 
+#[get("/auction")]
+/// The street or house on auction, whose turn it is to bid, and the lowest bid they can make.
+// Expects a request like:
+// curl http://127.0.0.1:8000/auction
+async fn auction(app_state: web::Data<AppState>) -> HttpResponse {
+    let g = app_state.game.lock().unwrap();
+    let response = match g.auction() {
+        Some(a) => serde_json::json!({
+            "lot": a.lot(),
+            "bidder": a.bidder(),
+            "bidders": a.bidders(),
+            "highest_bid": a.highest_bid(),
+            "minimum_bid": a.minimum_bid()
+        }),
+        None => serde_json::Value::Null
+    };
     HttpResponse::Ok()
         .content_type("application/json; charset=utf-8")
-        .body(response)
+        .body(response.to_string())
+}
+
+#[post("/auction/bid")]
+/// Raise the highest bid, on behalf of the player whose turn it is.
+// Expects a request like:
+// curl -X POST -H "Content-type: application/json" \
+//      -d '{"amount": 120}' \
+//      http://127.0.0.1:8000/auction/bid
+async fn bid(app_state: web::Data<AppState>, bid: web::Json<BidAmount>) -> HttpResponse {
+    info!("Got {:?}", bid);
+    let mut g = app_state.game.lock().unwrap();
+    events_response(g.apply(GameCommand::Bid { amount: bid.amount }))
+}
+
+#[post("/auction/pass")]
+/// Drop out of the auction, on behalf of the player whose turn it is.
+// Expects a request like:
+// curl -X POST http://127.0.0.1:8000/auction/pass
+async fn pass_bid(app_state: web::Data<AppState>) -> HttpResponse {
+    let mut g = app_state.game.lock().unwrap();
+    events_response(g.apply(GameCommand::PassBid))
+}
+
+#[post("/income-tax")]
+/// Pay the income tax as a percentage of the net worth, or the flat amount.
+// Expects a request like:
+// curl -X POST -H "Content-type: application/json" \
+//      -d '{"net_worth": true}' \
+//      http://127.0.0.1:8000/income-tax
+async fn pay_income_tax(app_state: web::Data<AppState>,
+                        choice: web::Json<TaxChoice>) -> HttpResponse {
+    info!("Got {:?}", choice);
+    let mut g = app_state.game.lock().unwrap();
+    events_response(g.apply(GameCommand::PayIncomeTax { net_worth: choice.net_worth }))
+}
+
+#[post("/trade")]
+/// Propose a trade to another player.
+// Expects a request like:
+// curl -X POST -H "Content-type: application/json" \
+//      -d '{"proposer": 0, "counterparty": 1,
+//           "gives": {"streets": [1, 3], "cash": 0, "jail_cards": 0},
+//           "receives": {"streets": [], "cash": 200, "jail_cards": 0}}' \
+//      http://127.0.0.1:8000/trade
+async fn propose_trade(app_state: web::Data<AppState>,
+                       trade: web::Json<Trade>) -> HttpResponse {
+    info!("Got {:?}", trade);
+    let mut g = app_state.game.lock().unwrap();
+    events_response(g.apply(GameCommand::ProposeTrade { trade: trade.into_inner() }))
+}
+
+#[post("/trade/accept")]
+/// Accept the proposed trade.
+// Expects a request like:
+// curl -X POST http://127.0.0.1:8000/trade/accept
+async fn accept_trade(app_state: web::Data<AppState>) -> HttpResponse {
+    let mut g = app_state.game.lock().unwrap();
+    events_response(g.apply(GameCommand::AcceptTrade))
+}
+
+#[post("/trade/reject")]
+/// Turn the proposed trade down.
+// Expects a request like:
+// curl -X POST http://127.0.0.1:8000/trade/reject
+async fn reject_trade(app_state: web::Data<AppState>) -> HttpResponse {
+    let mut g = app_state.game.lock().unwrap();
+    events_response(g.apply(GameCommand::RejectTrade))
+}
+
+#[post("/trade/counter")]
+/// Answer the proposed trade with a different one, as seen from the counterparty.
+// Expects a request like:
+// curl -X POST -H "Content-type: application/json" \
+//      -d '{"gives": {"streets": [], "cash": 150, "jail_cards": 0},
+//           "receives": {"streets": [1, 3], "cash": 0, "jail_cards": 0}}' \
+//      http://127.0.0.1:8000/trade/counter
+async fn counter_trade(app_state: web::Data<AppState>,
+                       offer: web::Json<CounterOffer>) -> HttpResponse {
+    info!("Got {:?}", offer);
+    let offer = offer.into_inner();
+    let mut g = app_state.game.lock().unwrap();
+    events_response(g.apply(GameCommand::CounterTrade {
+        gives: offer.gives, receives: offer.receives }))
+}
+
+#[get("/standings")]
+/// Players ranked by their net worth, and the winner once the game is over.
+// Expects a request like:
+// curl http://127.0.0.1:8000/standings
+async fn standings(app_state: web::Data<AppState>) -> HttpResponse {
+    let g = app_state.game.lock().unwrap();
+    let response = serde_json::json!({
+        "winner": g.winner(),
+        "standings": g.standings()
+    });
+    HttpResponse::Ok()
+        .content_type("application/json; charset=utf-8")
+        .body(response.to_string())
 }
 
 #[actix_web::main]
 async fn main() -> std::io::Result<()> {
     env_logger::init_from_env(env_logger::Env::new().default_filter_or("debug"));
 
-    let app_data = web::Data::new(AppState { 
-        num: Mutex::new(0),
-//        game: Mutex::new(game::init(vec![
-//                "Hannah".to_string(),
-//                "Daniel".to_string(),
-//                "Daddy".to_string(),
-//        ]))
+    let app_data = web::Data::new(AppState {
+        game: Mutex::new(game::init(vec![
+                "Hannah".to_string(),
+                "Daniel".to_string(),
+                "Daddy".to_string(),
+        ], game::GameMode::Classic, rules::Rules::official()))
     });
     HttpServer::new(move || {
         // TODO: Only for local testing
@@ -74,16 +258,23 @@ async fn main() -> std::io::Result<()> {
         App::new()
             .wrap(Logger::default())
             .wrap(cors)
-//            .app_data(app_data)
             .app_data(app_data.clone())
-//            .app_data(web::Data::new(AppState {
-//                num: Mutex::new(0),
-//                game: Mutex::new(game::init(
-//                        vec!["Hannah".to_string(), "Daniel".to_string(),
-//                             "Daddy".to_string()]))
-//            }))
             .service(ping)
             .service(roll_dice)
+            .service(command)
+            .service(undo)
+            .service(redo)
+            .service(end_game)
+            .service(standings)
+            .service(decline_purchase)
+            .service(auction)
+            .service(bid)
+            .service(pass_bid)
+            .service(pay_income_tax)
+            .service(propose_trade)
+            .service(accept_trade)
+            .service(reject_trade)
+            .service(counter_trade)
     })
     .bind(("localhost", 8000))?
     .run()
@@ -0,0 +1,139 @@
+use serde::{Deserialize, Serialize};
+
+use super::error::GameError;
+
+pub const OPENING_BID: u32 = 10; // lowest first bid on a street
+const MIN_INCREMENT: u32 = 10; // every bid raises the highest bid by at least this much
+
+/// Offer of a player on the street on auction
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub struct Bid {
+    pub player: usize, // turn_idx of the player
+    pub amount: u32
+}
+
+/// What is on auction
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub enum Lot {
+    Street(usize), // board index of the street
+    House // one of the last houses of the bank, built by the winner
+}
+
+/// Open-outcry auction of a street, or of a house
+// The bidders take turns to raise the highest bid, or to pass. A player who passes
+// is out of the auction. The lot is sold once all other bidders passed, and
+// nobody buys it when every bidder passes without a bid.
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub struct Auction {
+    lot: Lot,
+    bidders: Vec<usize>, // players still bidding, in turn order
+    next: usize, // index in bidders of the player whose turn it is
+    opening_bid: u32,
+    highest_bid: Option<Bid>
+}
+
+impl Auction {
+    pub fn new(lot: Lot, bidders: Vec<usize>, opening_bid: u32) -> Self {
+        Self {
+            lot,
+            bidders,
+            next: 0,
+            opening_bid,
+            highest_bid: None
+        }
+    }
+
+    pub fn lot(&self) -> Lot {
+        self.lot
+    }
+
+    pub fn bidders(&self) -> &[usize] {
+        &self.bidders
+    }
+
+    pub fn highest_bid(&self) -> Option<Bid> {
+        self.highest_bid
+    }
+
+    /// Player whose turn it is to bid or pass
+    pub fn bidder(&self) -> Option<usize> {
+        self.bidders.get(self.next).copied()
+    }
+
+    /// Lowest amount the next bid can be
+    pub fn minimum_bid(&self) -> u32 {
+        match self.highest_bid {
+            None    => self.opening_bid,
+            Some(b) => b.amount + MIN_INCREMENT
+        }
+    }
+
+    /// The bidder whose turn it is raises the highest bid
+    pub fn bid(&mut self, amount: u32) -> Result<Bid, GameError> {
+        let player = self.bidder().ok_or(GameError::NoAuction)?;
+        let minimum = self.minimum_bid();
+        if amount < minimum {
+            return Err(GameError::BidTooLow { minimum });
+        }
+        let bid = Bid { player, amount };
+        self.highest_bid = Some(bid);
+        self.next = (self.next + 1) % self.bidders.len();
+        Ok(bid)
+    }
+
+    /// The bidder whose turn it is drops out of the auction
+    pub fn pass(&mut self) -> Result<usize, GameError> {
+        let player = self.bidder().ok_or(GameError::NoAuction)?;
+        self.bidders.remove(self.next);
+        if self.next >= self.bidders.len() {
+            self.next = 0;
+        }
+        Ok(player)
+    }
+
+    /// Winning bid, once all other bidders passed
+    pub fn winner(&self) -> Option<Bid> {
+        match (self.highest_bid, self.bidders.as_slice()) {
+            (Some(b), [p]) if b.player == *p => Some(b),
+            _ => None
+        }
+    }
+
+    /// Check if the street is sold, or if all bidders passed
+    pub fn is_over(&self) -> bool {
+        self.bidders.is_empty() || self.winner().is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn highest_bidder_wins() {
+        let mut a = Auction::new(Lot::Street(3), vec![1, 2, 0], OPENING_BID);
+        assert_eq!(a.bidder(), Some(1));
+        assert_eq!(a.bid(5), Err(GameError::BidTooLow { minimum: 10 }));
+        assert_eq!(a.bid(20), Ok(Bid { player: 1, amount: 20 }));
+        assert_eq!(a.bid(25), Err(GameError::BidTooLow { minimum: 30 }));
+        assert_eq!(a.bid(40), Ok(Bid { player: 2, amount: 40 }));
+        assert_eq!(a.pass(), Ok(0));
+        assert_eq!(a.bidder(), Some(1));
+        assert!(!a.is_over());
+        assert_eq!(a.pass(), Ok(1));
+        assert_eq!(a.winner(), Some(Bid { player: 2, amount: 40 }));
+        assert!(a.is_over());
+    }
+
+    #[test]
+    fn nobody_bids() {
+        let mut a = Auction::new(Lot::House, vec![0, 1], 50);
+        assert_eq!(a.minimum_bid(), 50);
+        assert_eq!(a.pass(), Ok(0));
+        assert!(!a.is_over()); // the last bidder can still bid
+        assert_eq!(a.pass(), Ok(1));
+        assert!(a.is_over());
+        assert_eq!(a.winner(), None);
+        assert_eq!(a.pass(), Err(GameError::NoAuction));
+    }
+}
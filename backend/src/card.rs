@@ -1,13 +1,41 @@
+use std::fs;
+
+use serde::{Deserialize, Serialize};
+
+use super::square::SquareType;
+
+/// Cards of the classic game, shipped with the crate
+pub const CLASSIC_DECKS: &str = include_str!("../data/cards.json");
+
+#[derive(Clone, PartialEq, Eq, Hash, Debug, Serialize, Deserialize)]
 pub enum CardAction {
     Movement,
-    RelativeMovement,
+    RelativeMovement, // amount is the number of steps, backwards if negative
+    MoveToNearest(SquareType), // amount multiplies the rent, see game::Rent
     Payment,
+    CollectFromPlayers, // amount is what every other player pays
+    PayPlayers, // amount is what the player pays to every other player
     Jail, 
     JailRelease, 
     Repairs 
 }
 
+/// Deck a card is drawn from, and goes back to
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Serialize, Deserialize)]
+pub enum Deck {
+    Chance,
+    CommunityChest
+}
+
+/// Chance and community chest cards a game starts with, before shuffling
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub struct Decks {
+    pub chance: Vec<Card>,
+    pub community_chest: Vec<Card>
+}
+
 /// Chance or Community chest card
+#[derive(Clone, PartialEq, Eq, Hash, Debug, Serialize, Deserialize)]
 pub struct Card {
     description: String,
     action: CardAction,
@@ -42,3 +70,158 @@ impl Card {
         &self.action
     }
 }
+
+impl Decks {
+    /// Cards of the classic game
+    pub fn classic() -> Self {
+        Self::from_json(CLASSIC_DECKS).expect("Classic decks should be valid")
+    }
+
+    /// Read the decks from a JSON document, and check every card
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        let decks: Decks = match serde_json::from_str(json) {
+            Ok(d)  => d,
+            Err(e) => return Err(format!("Invalid decks: {}", e))
+        };
+        decks.validate()?;
+        Ok(decks)
+    }
+
+    /// Read the decks from a file
+    pub fn load_from_file(path: &str) -> Result<Self, String> {
+        match fs::read_to_string(path) {
+            Ok(json) => Self::from_json(&json),
+            Err(e)   => Err(format!("Cannot read the decks {}: {}", path, e))
+        }
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if self.chance.is_empty() {
+            return Err("The chance deck has no cards".to_string());
+        }
+        if self.community_chest.is_empty() {
+            return Err("The community chest deck has no cards".to_string());
+        }
+        // players keep get-out-of-jail-free cards, and could empty such a deck
+        for (name, cards) in [("chance", &self.chance),
+                              ("community chest", &self.community_chest)].iter() {
+            if cards.iter().all(|c| c.action == CardAction::JailRelease) {
+                return Err(format!("The {} deck has only get-out-of-jail-free cards", name));
+            }
+        }
+        self.check(Card::validate)
+    }
+
+    /// Check the cards only move players to squares on a board of this size
+    pub fn fit_board(&self, board_size: usize) -> Result<(), String> {
+        self.check(|card| match (&card.action, card.square) {
+            (CardAction::Movement, Some(s)) if s as usize >= board_size => {
+                Err(format!("moves to square {}, past the end of the board", s))
+            },
+            _ => Ok(())
+        })
+    }
+
+    /// Check every card of both decks
+    fn check<F>(&self, rule: F) -> Result<(), String>
+        where F: Fn(&Card) -> Result<(), String> {
+        for (name, cards) in [("chance", &self.chance),
+                              ("community chest", &self.community_chest)].iter() {
+            for (i, card) in cards.iter().enumerate() {
+                if let Err(e) = rule(card) {
+                    return Err(format!("Card {} of the {} deck (\"{}\") {}",
+                                       i + 1, name, card.description, e));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Card {
+    /// Check the card has the amount and square its action needs
+    fn validate(&self) -> Result<(), String> {
+        let amount = || self.amount.ok_or("needs an amount");
+        let square = || self.square.ok_or("needs a square");
+        match self.action {
+            CardAction::Movement => {
+                square()?;
+            },
+            CardAction::RelativeMovement => {
+                if amount()? == 0 {
+                    return Err("needs a number of steps other than 0".to_string());
+                }
+            },
+            CardAction::MoveToNearest(square_type) => {
+                if square_type != SquareType::Station && square_type != SquareType::Utility {
+                    return Err(format!("moves to the nearest {:?}, instead of a station or utility",
+                                       square_type));
+                }
+                if amount()? <= 0 {
+                    return Err("needs a positive rent multiplier".to_string());
+                }
+            },
+            CardAction::Payment => {
+                if amount()? == 0 {
+                    return Err("needs an amount other than 0".to_string());
+                }
+            },
+            CardAction::CollectFromPlayers | CardAction::PayPlayers => {
+                if amount()? <= 0 {
+                    return Err("needs a positive amount".to_string());
+                }
+            },
+            CardAction::Repairs => { // amount per house, square per hotel
+                if amount()? < 0 {
+                    return Err("can't have a negative amount per house".to_string());
+                }
+                square()?;
+            },
+            CardAction::Jail | CardAction::JailRelease => {}
+        };
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classic_decks_are_valid() {
+        let decks = Decks::classic();
+        assert_eq!(decks.chance.len(), 18);
+        assert_eq!(decks.community_chest.len(), 16);
+        let lottery = decks.chance.iter()
+            .find(|c| c.description().starts_with("You win the lottery"))
+            .unwrap();
+        assert_eq!(lottery.amount(), Some(-500));
+    }
+
+    #[test]
+    fn invalid_cards_are_rejected() {
+        let deck = |card: &str| format!(
+            r#"{{"chance": [{}], "community_chest": [{{"description": "Jail", "action": "Jail"}}]}}"#,
+            card);
+        assert_eq!(Decks::from_json(&deck(r#"{"description": "Fine", "action": "Payment"}"#)),
+                   Err("Card 1 of the chance deck (\"Fine\") needs an amount".to_string()));
+        let decks = Decks::from_json(
+            &deck(r#"{"description": "Go", "action": "Movement", "square": 40}"#)).unwrap();
+        assert_eq!(decks.fit_board(40),
+                   Err("Card 1 of the chance deck (\"Go\") moves to square 40, past the end of the board"
+                       .to_string()));
+        assert_eq!(decks.fit_board(41), Ok(()));
+        let repairs = r#"{"description": "Fix", "action": "Repairs", "amount": -1, "square": 9}"#;
+        assert_eq!(Decks::from_json(&deck(repairs)),
+                   Err("Card 1 of the chance deck (\"Fix\") can't have a negative amount per house"
+                       .to_string()));
+        let back = r#"{"description": "Back", "action": "RelativeMovement", "amount": -45}"#;
+        assert_eq!(Decks::from_json(&deck(back)).unwrap().fit_board(40), Ok(()));
+        assert!(Decks::from_json(&deck(r#"{"description": "Fly", "action": "Teleport"}"#))
+                .unwrap_err().starts_with("Invalid decks: "));
+        assert_eq!(Decks::from_json(r#"{"chance": [], "community_chest": []}"#),
+                   Err("The chance deck has no cards".to_string()));
+        assert_eq!(Decks::from_json(&deck(r#"{"description": "Free", "action": "JailRelease"}"#)),
+                   Err("The chance deck has only get-out-of-jail-free cards".to_string()));
+    }
+}
@@ -0,0 +1,67 @@
+use serde::{Deserialize, Serialize};
+
+use super::trade::{Trade, TradeOffer};
+
+/// Everything a frontend can ask the game to do
+// Streets are referenced by their index on the board, and players by their turn_idx.
+// Actions on streets are executed on behalf of the owner of that street.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GameCommand {
+    /// Dice rolled by the active player
+    RollDice(u32, u32),
+    /// Active player buys the street they landed on, at the listed price
+    BuyProperty,
+    /// Active player doesn't buy the street. In an auction, nobody buys it
+    DeclinePurchase,
+    /// The bidder whose turn it is raises the highest bid
+    Bid { amount: u32 },
+    /// The bidder whose turn it is drops out of the auction
+    PassBid,
+    BuyHouse { street: usize },
+    /// When there aren't enough houses for everyone who wants to build, one of the
+    /// last houses goes on auction among them. The winner then builds it with `BuyHouse`
+    AuctionHouse,
+    SellHouse { street: usize },
+    BuyHotel { street: usize },
+    SellHotel { street: usize },
+    Mortgage { street: usize },
+    Unmortgage { street: usize },
+    /// New owner of a mortgaged street doesn't lift the mortgage yet. Lifting it
+    /// later costs another 10% interest. Lifting it now is `Unmortgage`
+    KeepMortgage { street: usize },
+    /// Active player pays the income tax they landed on: a percentage of their net
+    /// worth, or the flat amount
+    PayIncomeTax { net_worth: bool },
+    /// Owner of the street sells it to another player
+    SellStreet { street: usize, buyer: usize, price: u32 },
+    /// A player proposes a trade to another player
+    ProposeTrade { trade: Trade },
+    /// The counterparty accepts the proposed trade
+    AcceptTrade,
+    /// The counterparty turns the proposed trade down
+    RejectTrade,
+    /// The counterparty proposes a different trade instead. What is given and
+    /// received is seen from the counterparty
+    CounterTrade { gives: TradeOffer, receives: TradeOffer },
+    /// Active player bribes the guards to leave jail, before rolling the dice
+    PayBail,
+    /// Active player leaves jail with a get-out-of-jail-free card, before rolling
+    /// the dice
+    UseJailCard,
+    /// Active player ends their turn. A player in trouble has to pay their debts first
+    EndTurn,
+    /// Active player leaves the game
+    LeaveGame,
+    /// A player in trouble gives up and hands everything over to their creditor,
+    /// also when it isn't their turn
+    GoBankrupt { player: usize },
+    /// All players agree to stop. The players are ranked by their net worth
+    EndGame,
+    /// Take back the last executed command
+    Undo,
+    /// Take back all commands of the current turn, or of the previous turn if
+    /// the current turn just started
+    UndoTurn,
+    /// Execute the last command that was taken back again
+    Redo
+}
@@ -0,0 +1,200 @@
+use std::collections::VecDeque;
+
+use super::dialog::{self, UserAction};
+use super::game::Dice;
+use super::player::Player;
+use super::square::Square;
+
+/// Source of every choice the game needs from its players
+// The game never reads from stdin itself. It asks the decision provider instead, so
+// the same engine can be driven from the terminal, the web server or a unit test.
+// Providers are `Send`, so the game can move between threads with its provider.
+pub trait DecisionProvider: Send {
+    /// The roll of the (physical) dice
+    fn roll_dice(&mut self) -> Dice;
+
+    /// Answer to a yes/no question, like buying a street or bribing the guards
+    fn yes_no(&mut self, message: &str) -> bool;
+
+    /// The next action from the menu, after the dice have been rolled
+    fn user_action(&mut self, is_in_trouble: bool) -> UserAction;
+
+    /// Index of a player still in the game. `exclude` is not a valid option
+    fn player_idx(&mut self, players: &[Player], exclude: Option<usize>, msg: &str)
+        -> Option<usize>;
+
+    /// Price paid for a street at auction
+    fn purchase_price(&mut self, square: &Square) -> Option<u32>;
+
+    /// Board index of one of the eligible streets
+    fn street(&mut self, eligible_streets: Vec<(usize, &Square)>) -> Option<usize>;
+
+    /// Amount of cash, like the price of a street sold to another player
+    fn amount(&mut self) -> Option<u32>;
+}
+
+/// Decisions captured from the player on the command line
+pub struct TerminalDecisions;
+
+impl DecisionProvider for TerminalDecisions {
+    fn roll_dice(&mut self) -> Dice {
+        dialog::capture_dice_roll()
+    }
+
+    fn yes_no(&mut self, message: &str) -> bool {
+        dialog::yes_no(message)
+    }
+
+    fn user_action(&mut self, is_in_trouble: bool) -> UserAction {
+        match is_in_trouble {
+            true  => dialog::trouble_user_actions(),
+            false => dialog::additional_user_actions()
+        }
+    }
+
+    fn player_idx(&mut self, players: &[Player], exclude: Option<usize>, msg: &str)
+            -> Option<usize> {
+        dialog::get_player_idx(players, exclude, msg).ok()
+    }
+
+    fn purchase_price(&mut self, square: &Square) -> Option<u32> {
+        dialog::get_purchase_price(square).ok()
+    }
+
+    fn street(&mut self, eligible_streets: Vec<(usize, &Square)>) -> Option<usize> {
+        dialog::get_street(eligible_streets).ok()
+    }
+
+    fn amount(&mut self) -> Option<u32> {
+        dialog::get_amount().ok()
+    }
+}
+
+/// A single answer for the scripted decision provider
+#[derive(Debug)]
+pub enum Decision {
+    Dice(u32, u32),
+    YesNo(bool),
+    Action(UserAction),
+    Player(usize),
+    Amount(u32),
+    Street(usize),
+    Quit // return to the menu
+}
+
+/// Decisions taken from a list of answers prepared in advance
+// Useful for unit tests, and for frontends that collect answers before calling the
+// game. Once the script runs out, every question is answered with 'yes' and the menu
+// ends the turn.
+pub struct ScriptedDecisions {
+    decisions: VecDeque<Decision>
+}
+
+impl ScriptedDecisions {
+    pub fn new() -> Self {
+        Self {
+            decisions: VecDeque::new()
+        }
+    }
+
+    /// Add an answer to the end of the script
+    pub fn push(&mut self, decision: Decision) {
+        self.decisions.push_back(decision);
+    }
+
+    fn next(&mut self) -> Option<Decision> {
+        self.decisions.pop_front()
+    }
+}
+
+impl Default for ScriptedDecisions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DecisionProvider for ScriptedDecisions {
+    fn roll_dice(&mut self) -> Dice {
+        match self.next() {
+            Some(Decision::Dice(d1, d2)) => Dice::new(d1, d2),
+            d => panic!("Expected a dice roll, but the script has {:?}", d)
+        }
+    }
+
+    fn yes_no(&mut self, _message: &str) -> bool {
+        match self.next() {
+            Some(Decision::YesNo(b)) => b,
+            None => true,
+            d => panic!("Expected yes/no, but the script has {:?}", d)
+        }
+    }
+
+    fn user_action(&mut self, _is_in_trouble: bool) -> UserAction {
+        match self.next() {
+            Some(Decision::Action(a)) => a,
+            None => UserAction::EndTurn,
+            d => panic!("Expected a menu action, but the script has {:?}", d)
+        }
+    }
+
+    fn player_idx(&mut self, _players: &[Player], _exclude: Option<usize>, _msg: &str)
+            -> Option<usize> {
+        match self.next() {
+            Some(Decision::Player(i)) => Some(i),
+            Some(Decision::Quit) | None => None,
+            d => panic!("Expected a player, but the script has {:?}", d)
+        }
+    }
+
+    fn purchase_price(&mut self, _square: &Square) -> Option<u32> {
+        self.amount()
+    }
+
+    fn street(&mut self, eligible_streets: Vec<(usize, &Square)>) -> Option<usize> {
+        match self.next() {
+            Some(Decision::Street(i)) => {
+                match eligible_streets.iter().any(|(idx, _)| *idx == i) {
+                    true  => Some(i),
+                    false => None
+                }
+            },
+            Some(Decision::Quit) | None => None,
+            d => panic!("Expected a street, but the script has {:?}", d)
+        }
+    }
+
+    fn amount(&mut self) -> Option<u32> {
+        match self.next() {
+            Some(Decision::Amount(a)) => Some(a),
+            Some(Decision::Quit) | None => None,
+            d => panic!("Expected an amount, but the script has {:?}", d)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scripted_answers_in_order() {
+        let mut d = ScriptedDecisions::new();
+        d.push(Decision::YesNo(false));
+        d.push(Decision::Dice(3, 4));
+        d.push(Decision::Amount(120));
+        assert_eq!(d.yes_no("Buy?"), false);
+        assert_eq!(d.roll_dice().roll(), (3, 4));
+        assert_eq!(d.amount(), Some(120));
+    }
+
+    #[test]
+    fn scripted_defaults_when_empty() {
+        let mut d = ScriptedDecisions::new();
+        assert_eq!(d.yes_no("Buy?"), true);
+        assert_eq!(d.amount(), None);
+        match d.user_action(false) {
+            UserAction::EndTurn => {},
+            _ => panic!("Expected the turn to end")
+        }
+    }
+}
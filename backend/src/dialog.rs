@@ -1,9 +1,11 @@
 use std::io;
 use std::io::{Write};
 
-use super::game::{Game, Dice};
+use super::game::Dice;
+use super::player::Player;
 use super::square::Square;
 
+#[derive(Debug)]
 pub enum UserAction {
     BuyHouse,
     SellHouse,
@@ -12,8 +14,13 @@ pub enum UserAction {
     Mortgage,
     Unmortgage,
     SellStreet,
+    Trade,
+    Undo,
+    UndoTurn,
+    Redo,
     EndTurn,
-    EndGame
+    EndGame,
+    FinishGame
 }
 
 /// Print actions a player can make outside of their turn
@@ -25,6 +32,11 @@ pub fn additional_user_actions() -> UserAction {
     println!("5. Sell hotel");
     println!("6. Mortgage street");
     println!("7. Unmortgage street");
+    println!("8. Undo last action");
+    println!("9. Undo turn");
+    println!("10. Redo");
+    println!("11. End the game for everyone");
+    println!("12. Propose a trade");
     println!("0. End turn");
     loop {
         print!("Select a valid option: ");
@@ -56,6 +68,21 @@ pub fn additional_user_actions() -> UserAction {
                     "7" => {
                         return UserAction::Unmortgage;
                     },
+                    "8" => {
+                        return UserAction::Undo;
+                    },
+                    "9" => {
+                        return UserAction::UndoTurn;
+                    },
+                    "10" => {
+                        return UserAction::Redo;
+                    },
+                    "11" => {
+                        return UserAction::FinishGame;
+                    },
+                    "12" => {
+                        return UserAction::Trade;
+                    },
                     _  => println!("Invalid option. Try again")
                 }
             },
@@ -73,6 +100,10 @@ pub fn trouble_user_actions() -> UserAction {
     println!("3. Sell hotel");
     println!("4. Mortgage street");
     println!("5. Continue");
+    println!("6. Undo last action");
+    println!("7. Undo turn");
+    println!("8. Redo");
+    println!("9. Propose a trade");
     println!("0. QUIT (LEAVE GAME)");
     loop {
         print!("Select a valid option: ");
@@ -87,6 +118,10 @@ pub fn trouble_user_actions() -> UserAction {
                     "3" => { return UserAction::SellHotel; },
                     "4" => { return UserAction::Mortgage; },
                     "5" => { return UserAction::EndTurn; },
+                    "6" => { return UserAction::Undo; },
+                    "7" => { return UserAction::UndoTurn; },
+                    "8" => { return UserAction::Redo; },
+                    "9" => { return UserAction::Trade; },
                     "0" => { return UserAction::EndGame; },
                     _  => println!("Invalid option. Try again")
                 }
@@ -169,19 +204,18 @@ pub fn yes_no(message: &str) -> bool {
 
 /// Capture the idx of a player from the user
 // This method is useful for out-of-band transactions. These include auctions and ad-hoc selling of property to others
-pub fn get_player_idx(game: &Game, player: Option<usize>, msg: &str) 
+pub fn get_player_idx(players: &[Player], player: Option<usize>, msg: &str)
         -> Result<usize, ()> {
     // Do not print current player
 
     let mut valid_options = Vec::<usize>::new();
-    for (i, p) in game.players().iter().enumerate() {
-        if p.borrow().left_game() { continue; }; // ignore players who've left the game
+    for (i, p) in players.iter().enumerate() {
+        if p.left_game() { continue; }; // ignore players who've left the game
         if player.is_some() {
             if player.unwrap() == i { continue; };
         }
         valid_options.push(i+1);
-        // Fails because current player is already borrowed somewhere else
-        println!("{}: {}", i+1, p.borrow().name());
+        println!("{}: {}", i+1, p.name());
     }
     println!("q: Quit, and return to the menu");
 
@@ -258,7 +292,7 @@ pub fn get_street(eligible_streets: Vec<(usize, &Square)>) -> Result<usize, ()>
             let title = format!("{}. {}", i+1, s.1.name());
             let sd = s.1.get_street_details()
                       .expect("owned streets doesn't have details");
-            let extra = match s.1.asset.borrow().is_mortgaged() {
+            let extra = match s.1.asset().is_mortgaged() {
                 true  => format!("unmortgage for ${}", sd.get_unmortgage_amount()),
                 false => format!("mortgage for ${}", sd.mortgage()),
             };
@@ -0,0 +1,215 @@
+use std::fs;
+
+use serde::{Deserialize, Serialize};
+
+use super::square::{Square, SquareType, StreetDetails, Suburb};
+
+/// Board of the United States edition, shipped with the crate
+pub const US_EDITION: &str = include_str!("../data/editions/us.json");
+
+/// Board of the United Kingdom edition, shipped with the crate
+pub const UK_EDITION: &str = include_str!("../data/editions/uk.json");
+
+/// Color of the streets in a suburb, and the price of every house or hotel
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub struct SuburbDefinition {
+    pub color: String,
+    pub building_price: u32
+}
+
+/// Square of the board, as described in the edition
+// Streets need all the details, stations and utilities only a price and mortgage.
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub struct SquareDefinition {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub square_type: SquareType,
+    pub suburb: Option<String>, // color of the suburb
+    pub price: Option<u32>,
+    pub rent: Option<u32>,
+    pub rent_suburb: Option<[u32; 6]>, // suburb, house 1..4, hotel
+    pub mortgage: Option<u32>
+}
+
+/// Squares, suburbs and rents of a board
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub struct Edition {
+    pub name: String,
+    pub suburbs: Vec<SuburbDefinition>,
+    pub squares: Vec<SquareDefinition>, // in the order of the board, starting at GO
+    pub station_rent: Vec<u32>, // rent when owning 1, 2.. stations
+    pub utility_rent: Vec<u32> // dice multiplied by this, when owning 1, 2.. utilities
+}
+
+impl Edition {
+    /// Board of the United States edition
+    pub fn us() -> Self {
+        Self::from_json(US_EDITION).expect("US edition should be valid")
+    }
+
+    /// Board of the United Kingdom edition
+    pub fn uk() -> Self {
+        Self::from_json(UK_EDITION).expect("UK edition should be valid")
+    }
+
+    /// Edition shipped with the crate, by its short name (eg uk)
+    pub fn named(name: &str) -> Option<Self> {
+        match name {
+            "us" => Some(Self::us()),
+            "uk" => Some(Self::uk()),
+            _    => None
+        }
+    }
+
+    /// Read the edition from a JSON document, and check every square
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        let edition: Edition = match serde_json::from_str(json) {
+            Ok(e)  => e,
+            Err(e) => return Err(format!("Invalid edition: {}", e))
+        };
+        edition.validate()?;
+        Ok(edition)
+    }
+
+    /// Read the edition from a file
+    pub fn load_from_file(path: &str) -> Result<Self, String> {
+        match fs::read_to_string(path) {
+            Ok(json) => Self::from_json(&json),
+            Err(e)   => Err(format!("Cannot read the edition {}: {}", path, e))
+        }
+    }
+
+    /// Check the board is complete, and every square has the details it needs
+    pub fn validate(&self) -> Result<(), String> {
+        if self.squares.first().map(|s| s.square_type) != Some(SquareType::Go) {
+            return Err("The board needs to start at GO".to_string());
+        }
+        let jails = self.squares.iter().filter(|s| s.square_type == SquareType::Jail).count();
+        if jails != 1 {
+            return Err(format!("The board needs one jail, but has {}", jails));
+        }
+        for (i, suburb) in self.suburbs.iter().enumerate() {
+            if self.suburbs[..i].iter().any(|s| s.color == suburb.color) {
+                return Err(format!("Suburb {} is defined twice", suburb.color));
+            }
+            if !self.squares.iter().any(|s| s.suburb.as_ref() == Some(&suburb.color)) {
+                return Err(format!("Suburb {} has no streets", suburb.color));
+            }
+        }
+        for (i, square) in self.squares.iter().enumerate() {
+            if let Err(e) = self.validate_square(square) {
+                return Err(format!("Square {} ({}) {}", i, square.name, e));
+            }
+        }
+
+        let count = |t| self.squares.iter().filter(|s| s.square_type == t).count();
+        if self.station_rent.len() < count(SquareType::Station) {
+            return Err(format!("Station rent is needed for up to {} stations",
+                               count(SquareType::Station)));
+        }
+        if self.utility_rent.len() < count(SquareType::Utility) {
+            return Err(format!("Utility rent is needed for up to {} utilities",
+                               count(SquareType::Utility)));
+        }
+        Ok(())
+    }
+
+    fn validate_square(&self, square: &SquareDefinition) -> Result<(), String> {
+        let needs = |value: bool, detail: &str| match value {
+            true  => Ok(()),
+            false => Err(format!("needs a {}", detail))
+        };
+        match square.square_type {
+            SquareType::Street => {
+                let color = square.suburb.as_ref().ok_or("needs a suburb")?;
+                if !self.suburbs.iter().any(|s| &s.color == color) {
+                    return Err(format!("is in suburb {}, which isn't defined", color));
+                }
+                needs(square.price.is_some(), "price")?;
+                needs(square.rent.is_some(), "rent")?;
+                needs(square.rent_suburb.is_some(), "rent for the suburb and buildings")?;
+                needs(square.mortgage.is_some(), "mortgage")?;
+            },
+            SquareType::Station | SquareType::Utility => {
+                needs(square.price.is_some(), "price")?;
+                needs(square.mortgage.is_some(), "mortgage")?;
+                if square.suburb.is_some() {
+                    return Err("can't be in a suburb".to_string());
+                }
+            },
+            SquareType::Tax { percent: Some(p), .. } if p > 100 => {
+                return Err("can't tax more than the net worth".to_string());
+            },
+            _ => {
+                if square.suburb.is_some() || square.price.is_some() {
+                    return Err("can't be bought".to_string());
+                }
+            }
+        };
+        Ok(())
+    }
+
+    /// Create the squares of the board, without owners
+    pub fn squares(&self) -> Vec<Square> {
+        self.squares.iter()
+            .map(|s| {
+                let suburb = s.suburb.as_ref()
+                    .and_then(|color| self.suburbs.iter().find(|sb| &sb.color == color))
+                    .map(|sb| Suburb::new(&sb.color, sb.building_price));
+                let details = s.price.map(|price| StreetDetails::new(
+                    suburb, price, s.rent.unwrap_or(0), s.rent_suburb.unwrap_or([0; 6]),
+                    s.mortgage.unwrap_or(0)));
+                Square::new(&s.name, s.square_type, details)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shipped_editions_are_valid() {
+        let us = Edition::us().squares();
+        assert_eq!(us[39].name(), "Boardwalk");
+        assert_eq!(us[39].get_price(), 400);
+        assert_eq!(us[5].get_price(), 200);
+        let uk = Edition::named("uk").unwrap().squares();
+        assert_eq!(uk[39].name(), "Mayfair");
+        assert_eq!(uk[39].get_street_details().unwrap().get_suburb().unwrap().color(),
+                   "Dark Blue");
+        assert_eq!(Edition::named("atlantis"), None);
+    }
+
+    #[test]
+    fn invalid_editions_are_rejected() {
+        let mut edition = Edition::us();
+        edition.squares[1].suburb = Some("Purple".to_string());
+        assert_eq!(edition.validate(),
+                   Err("Square 1 (Mediterranean Avenue) is in suburb Purple, which isn't defined"
+                       .to_string()));
+
+        let mut edition = Edition::us();
+        edition.squares[5].mortgage = None;
+        assert_eq!(edition.validate(),
+                   Err("Square 5 (Reading Railroad) needs a mortgage".to_string()));
+
+        let mut edition = Edition::us();
+        edition.squares[4].square_type = SquareType::Tax { amount: 200, percent: Some(110) };
+        assert_eq!(edition.validate(),
+                   Err("Square 4 (Income Tax) can't tax more than the net worth".to_string()));
+
+        let mut edition = Edition::us();
+        edition.station_rent.pop();
+        assert_eq!(edition.validate(),
+                   Err("Station rent is needed for up to 4 stations".to_string()));
+
+        let mut edition = Edition::us();
+        edition.squares.remove(10);
+        assert_eq!(edition.validate(), Err("The board needs one jail, but has 0".to_string()));
+        edition.squares.remove(0);
+        assert_eq!(edition.validate(), Err("The board needs to start at GO".to_string()));
+        assert!(Edition::from_json("{}").unwrap_err().starts_with("Invalid edition: "));
+    }
+}
@@ -0,0 +1,130 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+/// Reason an action or command failed
+// Players are referenced by their turn_idx. Frontends can match on the variant to
+// present their own messages; `Display` gives the default English message.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GameError {
+    InsufficientFunds { needed: u32, available: u32 },
+    OutstandingDebt { amount: u32 },
+    NoSuchPlayer,
+    NotInTrouble,
+    PlayerInTrouble { player: usize },
+    NoSuchStreet,
+    PlayerLeftGame { player: usize },
+    Unowned,
+    NotOwner,
+    SameOwner,
+    SuburbIncomplete,
+    NotBuildable,
+    UnevenBuilding,
+    HasBuildings,
+    MaxHouses,
+    NoHouses,
+    HousesRequired,
+    HasHotel,
+    NoHotel,
+    NotEnoughHouses { needed: u32, available: u32 },
+    NoHotelsLeft,
+    HouseShortage,
+    NoHouseShortage,
+    HouseBidPending,
+    BidTooLow { minimum: u32 },
+    AlreadyMortgaged,
+    NotMortgaged,
+    NotInJail,
+    NoJailCard,
+    InvalidDice,
+    DiceAlreadyRolled,
+    DiceNotRolled,
+    PurchasePending,
+    NothingToBuy,
+    NoAuction,
+    NoTrade,
+    NoMortgageChoice,
+    MortgageChoicePending,
+    RentRollPending,
+    NoTaxChoice,
+    TaxChoicePending,
+    EmptyTrade,
+    TradeWithSelf,
+    DuplicateStreet,
+    NothingToUndo,
+    NothingToRedo,
+    GameOver,
+    ReplayMismatch { entry: usize }
+}
+
+impl fmt::Display for GameError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GameError::InsufficientFunds { needed, available } =>
+                write!(f, "Not enough cash: ${} is needed, but only ${} is available",
+                       needed, available),
+            GameError::OutstandingDebt { amount } =>
+                write!(f, "You still owe ${}. Sell assets, or leave the game", amount),
+            GameError::NoSuchPlayer => write!(f, "There is no such player"),
+            GameError::NotInTrouble => write!(f, "The player has no debts"),
+            GameError::PlayerInTrouble { .. } =>
+                write!(f, "Another player still owes money. They sell assets, or leave the game"),
+            GameError::NoSuchStreet => write!(f, "There is no such street"),
+            GameError::PlayerLeftGame { .. } => write!(f, "The player has left the game"),
+            GameError::Unowned => write!(f, "This street is unowned"),
+            GameError::NotOwner => write!(f, "You don't own this street"),
+            GameError::SameOwner => write!(f, "The street cannot be sold to its owner"),
+            GameError::SuburbIncomplete =>
+                write!(f, "You don't own all the streets in the suburb"),
+            GameError::NotBuildable => write!(f, "This street cannot have buildings"),
+            GameError::UnevenBuilding =>
+                write!(f, "Buildings must be spread evenly. Build or sell on other streets first"),
+            GameError::HasBuildings => write!(f, "The street has buildings. Sell them first"),
+            GameError::MaxHouses => write!(f, "This street cannot have more houses"),
+            GameError::NoHouses => write!(f, "This street has no houses"),
+            GameError::HousesRequired =>
+                write!(f, "You need 4 houses before you can buy a hotel"),
+            GameError::HasHotel => write!(f, "This street has a hotel"),
+            GameError::NoHotel => write!(f, "This street has no hotel"),
+            GameError::NotEnoughHouses { needed, available } =>
+                write!(f, "The bank has {} houses left, but {} are needed", available, needed),
+            GameError::NoHotelsLeft => write!(f, "The bank has no hotels left"),
+            GameError::HouseShortage =>
+                write!(f, "There aren't enough houses for everyone. Auction them off"),
+            GameError::NoHouseShortage =>
+                write!(f, "There are enough houses for everyone. Buy them from the bank"),
+            GameError::HouseBidPending => write!(f, "Build the house won at auction first"),
+            GameError::BidTooLow { minimum } =>
+                write!(f, "The bid must be at least ${}", minimum),
+            GameError::AlreadyMortgaged => write!(f, "Street is already mortgaged"),
+            GameError::NotMortgaged => write!(f, "Street isn't mortgaged"),
+            GameError::NotInJail => write!(f, "You are not in jail"),
+            GameError::NoJailCard => write!(f, "You don't have a get-out-of-jail-free card"),
+            GameError::InvalidDice => write!(f, "Enter 2 numbers between 1 and 6"),
+            GameError::DiceAlreadyRolled => write!(f, "The dice have already been rolled"),
+            GameError::DiceNotRolled => write!(f, "Roll the dice first"),
+            GameError::PurchasePending => write!(f, "Decide who buys the street first"),
+            GameError::NothingToBuy => write!(f, "There is no street to buy"),
+            GameError::NoAuction => write!(f, "There is no auction"),
+            GameError::NoMortgageChoice =>
+                write!(f, "The mortgage of this street can't be kept without lifting it"),
+            GameError::MortgageChoicePending =>
+                write!(f, "Decide first to lift the mortgage of a street that changed hands"),
+            GameError::RentRollPending => write!(f, "Roll the dice for the rent first"),
+            GameError::NoTaxChoice => write!(f, "There is no income tax to choose"),
+            GameError::TaxChoicePending =>
+                write!(f, "Choose first how to pay the income tax"),
+            GameError::NoTrade => write!(f, "There is no trade to answer"),
+            GameError::EmptyTrade => write!(f, "Nothing changes hands in this trade"),
+            GameError::TradeWithSelf => write!(f, "Players can't trade with themselves"),
+            GameError::DuplicateStreet => write!(f, "A street is part of the trade twice"),
+            GameError::NothingToUndo => write!(f, "Nothing to undo"),
+            GameError::NothingToRedo => write!(f, "Nothing to redo"),
+            GameError::GameOver => write!(f, "The game is over"),
+            GameError::ReplayMismatch { entry } =>
+                write!(f, "Replay differs from the journal at entry {}", entry)
+        }
+    }
+}
+
+impl std::error::Error for GameError {}
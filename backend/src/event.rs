@@ -0,0 +1,226 @@
+use serde::{Deserialize, Serialize};
+
+use super::command::GameCommand;
+use super::error::GameError;
+use super::game::{Game, Standing, JAIL_TURNS};
+use super::player::{Creditor, Debt};
+use super::trade::{Trade, TradeOffer};
+
+/// How a player got out of jail
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum JailRelease {
+    Card,
+    Bail { amount: u32 },
+    Double,
+    TimeServed { bail: u32 } // forced to pay the bail after the last turn in jail
+}
+
+/// Outcome of a command, for the frontend to display
+// Players are referenced by their turn_idx, and squares by their index on the board
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GameEvent {
+    TurnStarted { player: usize },
+    RolledDouble { player: usize },
+    Moved { player: usize, from: usize, to: usize },
+    PassedGo { player: usize, amount: u32 },
+    PurchaseOffered { player: usize, square: usize, price: u32 },
+    CannotAfford { player: usize, square: usize },
+    AuctionStarted { square: usize },
+    BidPlaced { player: usize, amount: u32 },
+    PassedBid { player: usize },
+    NoBids { square: usize },
+    HouseAuctionStarted { opening_bid: u32 },
+    WonHouse { player: usize, price: u32 }, // the house is built once they choose a street
+    BoughtProperty { player: usize, square: usize, price: u32 },
+    NoRent { player: usize, square: usize, mortgaged: bool },
+    OwnerInJail { player: usize, owner: usize, square: usize },
+    PaidRent { player: usize, owner: usize, square: usize, amount: u32 },
+    RollForRent { player: usize, owner: usize, square: usize, factor: u32 },
+    PaidTax { player: usize, square: usize, amount: u32 },
+    DrewCard { player: usize, square: usize, description: String },
+    DeckEmpty { player: usize, square: usize }, // the players hold every card of the deck
+    PaidBank { player: usize, amount: u32 },
+    PaidPlayer { player: usize, to: usize, amount: u32 },
+    CollectedFromBank { player: usize, amount: u32 },
+    WonJackpot { player: usize, amount: u32 },
+    ReceivedJailCard { player: usize },
+    WentToJail { player: usize },
+    LeftJail { player: usize, release: JailRelease },
+    ServedJailTurn { player: usize, turns: u32 },
+    BecameInTrouble { player: usize, debt: Debt },
+    OutOfTrouble { player: usize },
+    SoldStreet { seller: usize, buyer: usize, square: usize, price: u32 },
+    BoughtHouse { player: usize, square: usize, price: u32 },
+    SoldHouse { player: usize, square: usize, price: u32 },
+    BoughtHotel { player: usize, square: usize, price: u32 },
+    SoldHotel { player: usize, square: usize, price: u32 },
+    Mortgaged { player: usize, square: usize, amount: u32 },
+    Unmortgaged { player: usize, square: usize, amount: u32 },
+    WentBankrupt { player: usize, creditor: Creditor },
+    PaidDebt { player: usize, creditor: Creditor, amount: u32 },
+    TransferredProperty { from: usize, to: usize, square: usize },
+    PaidInterest { player: usize, square: usize, amount: u32 },
+    KeptMortgage { player: usize, square: usize },
+    ReturnedToBank { player: usize, square: usize },
+    TradeProposed { trade: Trade },
+    TradeRejected { player: usize },
+    Traded { trade: Trade },
+    LeftGame { player: usize },
+    /// The players are ranked by their net worth. The winner comes first
+    GameOver { winner: usize, standings: Vec<Standing> },
+    Undone { command: GameCommand },
+    Redone { command: GameCommand },
+    /// The command wasn't executed
+    Rejected { error: GameError }
+}
+
+impl GameEvent {
+    /// Human readable description of the event
+    pub fn describe(&self, game: &Game) -> String {
+        let name = |p: &usize| game.players().get(*p)
+            .expect("Player should exist").name().to_string();
+        let square = |s: &usize| game.board().get(*s)
+            .expect("Square should exist").name().to_string();
+        let offer = |o: &TradeOffer| {
+            let mut items = o.streets.iter().map(square).collect::<Vec<String>>();
+            if o.cash > 0 {
+                items.push(format!("${}", o.cash));
+            }
+            match o.jail_cards {
+                0 => {},
+                1 => items.push(String::from("a get-out-of-jail-free card")),
+                n => items.push(format!("{} get-out-of-jail-free cards", n))
+            };
+            match items.is_empty() {
+                true  => String::from("nothing"),
+                false => items.join(", ")
+            }
+        };
+
+        match self {
+            GameEvent::TurnStarted { player } =>
+                format!("\n=== {}, Your turn ===", name(player)),
+            GameEvent::RolledDouble { .. } => String::from("A double. Roll again"),
+            GameEvent::Moved { to, .. } => format!("You landed on {}", square(to)),
+            GameEvent::PassedGo { amount, .. } =>
+                format!("Yay! You pass begin and collect ${}", amount),
+            GameEvent::PurchaseOffered { square: s, price, .. } =>
+                format!("{} is for sale for ${}", square(s), price),
+            GameEvent::CannotAfford { .. } =>
+                String::from("You can't afford to buy this street."),
+            GameEvent::AuctionStarted { .. } => String::from("Auction!!"),
+            GameEvent::BidPlaced { player, amount } =>
+                format!("{} bids ${}", name(player), amount),
+            GameEvent::PassedBid { player } => format!("{} passes", name(player)),
+            GameEvent::NoBids { square: s } =>
+                format!("Nobody bids on {}. It stays with the bank", square(s)),
+            GameEvent::HouseAuctionStarted { opening_bid } =>
+                format!("Not enough houses for everyone! Bidding starts at ${}", opening_bid),
+            GameEvent::WonHouse { player, price } =>
+                format!("{} wins the house for ${}. Choose a street to build it on",
+                        name(player), price),
+            GameEvent::BoughtProperty { player, square: s, price } =>
+                format!("{} buys {} for ${}", name(player), square(s), price),
+            GameEvent::NoRent { player, square: s, mortgaged } => match mortgaged {
+                true  => format!("Phew! {} is mortgaged, so no rent is due", square(s)),
+                false => format!("Phew! Luckily it's yours, {}", name(player))
+            },
+            GameEvent::OwnerInJail { owner, square: s, .. } =>
+                format!("Phew! {} is in jail, so no rent is due for {}", name(owner), square(s)),
+            GameEvent::PaidRent { owner, amount, .. } =>
+                format!("Oh no! You pay ${} to {}", amount, name(owner)),
+            GameEvent::RollForRent { owner, factor, .. } =>
+                format!("Roll the dice again, and pay {} {} times the roll", name(owner), factor),
+            GameEvent::PaidTax { square: s, amount, .. } =>
+                format!("Oh No! Pay ${} in {}!", amount, square(s)),
+            GameEvent::DrewCard { square: s, description, .. } =>
+                format!("{}! {}", square(s).to_uppercase(), description),
+            GameEvent::DeckEmpty { square: s, .. } =>
+                format!("{}! The players hold every card, so nothing happens",
+                        square(s).to_uppercase()),
+            GameEvent::PaidBank { amount, .. } =>
+                format!("You pay ${} to the bank", amount),
+            GameEvent::PaidPlayer { player, to, amount } =>
+                format!("{} pays ${} to {}", name(player), amount, name(to)),
+            GameEvent::CollectedFromBank { amount, .. } =>
+                format!("You collect ${} from the bank", amount),
+            GameEvent::WonJackpot { amount, .. } =>
+                format!("Jackpot! You collect ${} on Free Parking", amount),
+            GameEvent::ReceivedJailCard { player } =>
+                format!("{} keeps the get-out-of-jail-free card", name(player)),
+            GameEvent::WentToJail { .. } => String::from("GO TO JAIL"),
+            GameEvent::LeftJail { release, .. } => match release {
+                JailRelease::Card =>
+                    String::from("Yay, No More Jail, thanks to your get-out-of-jail-free card"),
+                JailRelease::Bail { amount } =>
+                    format!("Yay, No More Jail, since you bribed the guards ${}", amount),
+                JailRelease::Double => String::from("YAY, you're released from jail"),
+                JailRelease::TimeServed { bail } =>
+                    format!("Your time is up. Pay ${} to the guards and leave jail", bail)
+            },
+            GameEvent::ServedJailTurn { turns, .. } =>
+                format!("No double. You stay in jail (turn {} of {})", turns, JAIL_TURNS),
+            GameEvent::BecameInTrouble { player, debt } => match debt.creditor {
+                Creditor::Bank => format!("Uh oh! {} doesn't have enough money, and owes ${} \
+                                           to the bank", name(player), debt.amount),
+                Creditor::Player(c) => format!("Uh oh! {} doesn't have enough money, and \
+                                                owes ${} to {}", name(player), debt.amount, name(&c))
+            },
+            GameEvent::OutOfTrouble { player } =>
+                format!("{} is out of trouble", name(player)),
+            GameEvent::SoldStreet { seller, buyer, square: s, price } =>
+                format!("{} sells {} to {} for ${}",
+                        name(seller), square(s), name(buyer), price),
+            GameEvent::BoughtHouse { square: s, price, .. } =>
+                format!("You buy a house on {} for ${}", square(s), price),
+            GameEvent::SoldHouse { square: s, price, .. } =>
+                format!("You sell a house on {} for ${}", square(s), price),
+            GameEvent::BoughtHotel { square: s, price, .. } =>
+                format!("You buy a hotel on {} for ${}", square(s), price),
+            GameEvent::SoldHotel { square: s, price, .. } =>
+                format!("You sell the hotel on {} for ${}", square(s), price),
+            GameEvent::Mortgaged { square: s, amount, .. } =>
+                format!("You mortgage {} for ${}", square(s), amount),
+            GameEvent::Unmortgaged { square: s, amount, .. } =>
+                format!("You unmortgage {} for ${}", square(s), amount),
+            GameEvent::WentBankrupt { player, creditor } => match creditor {
+                Creditor::Bank => format!("{} is bankrupt, and owes the bank", name(player)),
+                Creditor::Player(c) =>
+                    format!("{} is bankrupt, and owes {}", name(player), name(c))
+            },
+            GameEvent::PaidDebt { player, creditor, amount } => match creditor {
+                Creditor::Bank => format!("{} pays ${} to the bank", name(player), amount),
+                Creditor::Player(c) =>
+                    format!("{} pays ${} to {}", name(player), amount, name(c))
+            },
+            GameEvent::TransferredProperty { from, to, square: s } =>
+                format!("{} hands {} over to {}", name(from), square(s), name(to)),
+            GameEvent::PaidInterest { player, square: s, amount } =>
+                format!("{} pays ${} interest on the mortgage of {}",
+                        name(player), amount, square(s)),
+            GameEvent::KeptMortgage { player, square: s } =>
+                format!("{} keeps the mortgage of {} for now", name(player), square(s)),
+            GameEvent::ReturnedToBank { square: s, .. } =>
+                format!("{} goes back to the bank", square(s)),
+            GameEvent::TradeProposed { trade } =>
+                format!("{} offers {} to {}, for {}", name(&trade.proposer),
+                        offer(&trade.gives), name(&trade.counterparty), offer(&trade.receives)),
+            GameEvent::TradeRejected { player } =>
+                format!("{} turns the trade down", name(player)),
+            GameEvent::Traded { trade } =>
+                format!("Deal! {} trades {} with {}, for {}", name(&trade.proposer),
+                        offer(&trade.gives), name(&trade.counterparty), offer(&trade.receives)),
+            GameEvent::LeftGame { player } => format!("== {} leaves the game ==", name(player)),
+            GameEvent::GameOver { winner, standings } => {
+                let ranking = standings.iter().enumerate()
+                    .map(|(i, s)| format!("{}. {} is worth ${}", i + 1, name(&s.player),
+                                          s.net_worth))
+                    .collect::<Vec<String>>();
+                format!("=== Game Over! {} wins ===\n{}", name(winner), ranking.join("\n"))
+            },
+            GameEvent::Undone { command } => format!("Undo: {:?}", command),
+            GameEvent::Redone { command } => format!("Redo: {:?}", command),
+            GameEvent::Rejected { error } => error.to_string()
+        }
+    }
+}
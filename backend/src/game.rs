@@ -1,255 +1,320 @@
-use rand::thread_rng;
+use rand::{thread_rng, Rng, SeedableRng};
+use rand::rngs::StdRng;
 use rand::seq::SliceRandom;
 
-use std::cell::RefCell;
+use std::time::{SystemTime, UNIX_EPOCH};
 
-use super::{card, dialog, player, publisher, square};
+use serde::{Deserialize, Serialize};
 
-const BOARD_SIZE: u32 = 40; // 40 squares on the board
+use super::{auction, card, decision, dialog, edition, journal, player, publisher, rules, save,
+            square, trade};
+use super::error::GameError;
+use super::command::GameCommand;
+use super::event::{GameEvent, JailRelease};
+
+pub const JAIL_TURNS: u32 = 3; // turns a player can try to roll a double in jail
+const HOUSES: u32 = 32; // houses the bank has at the start of the game
+const HOTELS: u32 = 12; // hotels the bank has at the start of the game
 
 mod actions {
     use super::Game;
-    use super::player::Player;
-    use super::dialog;
-
-    pub fn sell_street(game: &Game, orig_owner: &mut Player, new_owner: &mut Player,
-                       street_idx: usize, purchase_price: u32) {
-        let board = game.board();
-        let square = board.get(street_idx).expect("Street should exist");
-        {
-            let asset = square.asset.borrow();
-            if asset.has_buildings() {
-                println!("The street has buildings. Sell them first");
-                return;
-            }
-            if new_owner.cash() < purchase_price {
-                println!("{} cannot afford the street", new_owner.name());
-                return;
-            }
+    use super::GameEvent;
+    use super::super::error::GameError;
+
+    pub fn sell_street(game: &mut Game, orig_owner: usize, new_owner: usize,
+                       street_idx: usize, purchase_price: u32) -> Result<(), GameError> {
+        let square = game.board.get(street_idx).expect("Street should exist");
+        if square.asset().has_buildings() {
+            return Err(GameError::HasBuildings);
         }
-
-        game.sell_property(&mut *orig_owner, &mut *new_owner,
-                           square, purchase_price);
+        game.sell_property(orig_owner, new_owner, street_idx, purchase_price)
     }
 
-    pub fn mortgage_street(game: &Game, owner: &mut Player, street_idx: usize) {
-        let board = game.board();
-        let square = board.get(street_idx).expect("Street should exist");
-        let mut asset = square.asset.borrow_mut();
+    pub fn mortgage_street(game: &mut Game, owner: usize, street_idx: usize)
+            -> Result<(), GameError> {
+        let square = game.board.get(street_idx).expect("Street should exist");
+        let asset = square.asset();
         if asset.has_buildings() {
-            println!("The street has buildings. Sell them first");
-            return;
+            return Err(GameError::HasBuildings);
+        }
+        if asset.is_mortgaged() {
+            return Err(GameError::AlreadyMortgaged);
         }
 
-        owner.transact_cash(square.get_street_details().unwrap().mortgage() as i32);
-        asset.mortgage();
+        let amount = square.get_street_details().unwrap().mortgage();
+        game.players[owner].transact_cash(amount as i32).expect("Receiving cash cannot fail");
+        game.board[street_idx].asset_mut().mortgage();
+        game.emit(GameEvent::Mortgaged { player: owner, square: street_idx, amount });
+        Ok(())
     }
 
-    pub fn unmortgage_street(game: &Game, owner: &mut Player, street_idx: usize) {
+    pub fn unmortgage_street(game: &mut Game, owner: usize, street_idx: usize)
+            -> Result<(), GameError> {
         let street = game.board.get(street_idx).expect("Street should exist");
-        let mut asset = street.asset.borrow_mut();
 
-        if !asset.is_mortgaged() {
-            println!("Street isn't mortgaged");
-        } else {
-            let sd = street.get_street_details().unwrap();
-            owner.transact_cash(-1 * (sd.get_unmortgage_amount() as i32));
-            asset.unmortgage();
+        if !street.asset().is_mortgaged() {
+            return Err(GameError::NotMortgaged);
+        }
+        // the interest was already paid when the street changed hands
+        let amount = match game.mortgage_choices.contains(&street_idx) {
+            true  => street.get_street_details().unwrap().mortgage(),
+            false => street.get_street_details().unwrap().get_unmortgage_amount()
+        };
+        game.players[owner].transact_cash(-(amount as i32))?;
+        game.board[street_idx].asset_mut().unmortgage();
+        game.mortgage_choices.retain(|&s| s != street_idx);
+        game.emit(GameEvent::Unmortgaged { player: owner, square: street_idx, amount });
+        Ok(())
+    }
+
+    /// Owner buys a house from the bank, or builds the house they won at auction
+    pub fn buy_house(game: &mut Game, owner: usize, street_idx: usize)
+            -> Result<(), GameError> {
+        match game.house_bid {
+            Some(bid) if bid.player == owner => {
+                build_house(game, owner, street_idx, Some(bid.amount))?;
+                game.house_bid = None;
+                return Ok(());
+            },
+            Some(_) => return Err(GameError::HouseBidPending),
+            None => {}
+        };
+        if game.house_shortage() {
+            return Err(GameError::HouseShortage);
         }
+        build_house(game, owner, street_idx, None)
     }
 
-    pub fn buy_house(game: &Game, owner: &mut Player, street_idx: usize) {
+    // Houses are bought at the building price, unless a price was bid
+    fn build_house(game: &mut Game, owner: usize, street_idx: usize, bid: Option<u32>)
+            -> Result<(), GameError> {
         let street = game.board.get(street_idx).expect("Street should exist");
         let building_price = match street.get_street_details()
                     .expect("Details should exist").get_suburb() {
-            None => {
-                println!("You cannot buy a building here");
-                return;
-            },
+            None => return Err(GameError::NotBuildable),
             Some(s) => s.building_price()
         };
-        match street.asset.borrow().owner() {
+        match street.asset().owner() {
             Some(i) => {
-                if i != owner.turn_idx() {
-                    println!("You don't own this street");
-                    return;
+                if i != owner {
+                    return Err(GameError::NotOwner);
                 }
             },
-            None => {
-                println!("This street is unowned");
-                return;
-            }
+            None => return Err(GameError::Unowned)
         };
 
-        if owner.cash() < building_price {
-            println!("You can't afford to buy a house here");
-            return;
+        let price = bid.unwrap_or(building_price);
+        if price < building_price {
+            return Err(GameError::BidTooLow { minimum: building_price });
+        }
+        let available = game.players[owner].cash();
+        if available < price {
+            return Err(GameError::InsufficientFunds { needed: price, available });
+        }
+        if game.houses_left() == 0 {
+            return Err(GameError::NotEnoughHouses { needed: 1, available: 0 });
         }
 
         // get streets in suburb not owned by player
-        if !game.player_owns_suburb(owner.turn_idx(), &street) {
-            println!("You don't own all the streets in the suburb");
-            return;
+        if !game.player_owns_suburb(owner, &street) {
+            return Err(GameError::SuburbIncomplete);
         }
 
         if !game.street_eligible_for_house(&street) {
-            println!("Cannot buy a house here yet. Buy houses on other streets first");
-            return;
+            return Err(GameError::UnevenBuilding);
         }
 
-        // buy immediately for unit test
-        if game.is_unit_test {
-            street.asset.borrow_mut().buy_house();
-            owner.transact_cash(-1 * (building_price as i32));
-            return;
-        }
-
-        let msg = format!("Confirm: Buy a house on {} for ${}?",
-                          street.name(), building_price);
-        match dialog::yes_no(&msg) {
-            false => {}, // do nothing
-            true => {
-                // Now buy house
-                street.asset.borrow_mut().buy_house();
-                owner.transact_cash(-1 * (building_price as i32));
-            }
-        };
+        game.board[street_idx].asset_mut().buy_house()?;
+        game.players[owner].transact_cash(-(price as i32))
+            .expect("Cash was checked");
+        game.emit(GameEvent::BoughtHouse { player: owner, square: street_idx, price });
+        Ok(())
     }
 
-    pub fn sell_house(game: &Game, owner: &mut Player, street_idx: usize) {
+    pub fn sell_house(game: &mut Game, owner: usize, street_idx: usize)
+            -> Result<(), GameError> {
         let street = game.board.get(street_idx).expect("Street should exist");
         let building_price = match street.get_street_details()
                     .expect("Details should exist").get_suburb() {
-            None => {
-                println!("You cannot sell a building here");
-                return;
-            },
+            None => return Err(GameError::NotBuildable),
             Some(s) => s.building_price()
         };
-        match street.asset.borrow().owner() {
+        match street.asset().owner() {
             Some(i) => {
-                if i != owner.turn_idx() {
-                    println!("You don't own this street");
-                    return;
+                if i != owner {
+                    return Err(GameError::NotOwner);
                 }
             },
-            None => {
-                println!("This street is unowned");
-                return;
-            }
+            None => return Err(GameError::Unowned)
         };
-        
+        if street.asset().has_hotel() {
+            return Err(GameError::HasHotel);
+        }
+
         if !game.street_eligible_for_house_sale(&street) {
-            println!("Cannot sell a house. Sell other houses first");
-            return;
+            return Err(GameError::UnevenBuilding);
         }
-        // Player can always sell hotel
-        street.asset.borrow_mut().sell_house();
-        owner.transact_cash(building_price as i32);
+        game.board[street_idx].asset_mut().sell_house()?;
+        game.players[owner].transact_cash(building_price as i32)
+            .expect("Receiving cash cannot fail");
+        game.emit(GameEvent::SoldHouse {
+            player: owner, square: street_idx, price: building_price });
+        Ok(())
     }
 
-    pub fn buy_hotel(game: &Game, owner: &mut Player, street_idx: usize) {
+    pub fn buy_hotel(game: &mut Game, owner: usize, street_idx: usize)
+            -> Result<(), GameError> {
         let street = game.board.get(street_idx).expect("Street should exist");
         let building_price = match street.get_street_details()
                     .expect("Details should exist").get_suburb() {
-            None => {
-                println!("You cannot buy a building here");
-                return;
-            },
+            None => return Err(GameError::NotBuildable),
             Some(s) => s.building_price()
         };
-        match street.asset.borrow().owner() {
+        match street.asset().owner() {
             Some(i) => {
-                if i != owner.turn_idx() {
-                    println!("You don't own this street");
-                    return;
+                if i != owner {
+                    return Err(GameError::NotOwner);
                 }
             },
-            None => {
-                println!("This street is unowned");
-                return;
-            }
+            None => return Err(GameError::Unowned)
         };
 
-        if owner.cash() < building_price {
-            println!("You can't afford to buy a hotel here");
-            return;
+        let available = game.players[owner].cash();
+        if available < building_price {
+            return Err(GameError::InsufficientFunds { needed: building_price, available });
         }
 
         // get streets in suburb not owned by player
-        if !game.player_owns_suburb(owner.turn_idx(), &street) {
-            println!("You don't own all the streets in the suburb");
-            return;
+        if !game.player_owns_suburb(owner, &street) {
+            return Err(GameError::SuburbIncomplete);
         }
 
         if !game.street_eligible_for_hotel(&street) {
-            println!("Cannot buy a hotel here yet.");
-            return;
+            return Err(GameError::UnevenBuilding);
         }
-
-        let msg = format!("Confirm: Buy a hotel on {} for ${}?",
-                          street.name(), building_price);
-
-        // buy immediately for unit test
-        if game.is_unit_test {
-            street.asset.borrow_mut().buy_hotel();
-            owner.transact_cash(-1 * (building_price as i32));
-            return;
+        if game.hotels_left() == 0 {
+            return Err(GameError::NoHotelsLeft);
         }
 
-        match dialog::yes_no(&msg) {
-            false => {}, // do nothing
-            true  => {
-                // Now buy hotel
-                street.asset.borrow_mut().buy_hotel();
-                owner.transact_cash(-1 * (building_price as i32));
-            }
-        };
+        game.board[street_idx].asset_mut().buy_hotel()?;
+        game.players[owner].transact_cash(-(building_price as i32))
+            .expect("Cash was checked");
+        game.emit(GameEvent::BoughtHotel {
+            player: owner, square: street_idx, price: building_price });
+        Ok(())
     }
 
-    pub fn sell_hotel(game: &Game, owner: &mut Player, street_idx: usize) {
+    pub fn sell_hotel(game: &mut Game, owner: usize, street_idx: usize)
+            -> Result<(), GameError> {
         let street = game.board.get(street_idx).expect("Street should exist");
         let building_price = match street.get_street_details()
                     .expect("Details should exist").get_suburb() {
-            None => {
-                println!("You cannot sell a building here");
-                return;
-            },
+            None => return Err(GameError::NotBuildable),
             Some(s) => s.building_price()
         };
-        match street.asset.borrow().owner() {
+        match street.asset().owner() {
             Some(i) => {
-                if i != owner.turn_idx() {
-                    println!("You don't own this street");
-                    return;
+                if i != owner {
+                    return Err(GameError::NotOwner);
                 }
             },
-            None => {
-                println!("This street is unowned");
-                return;
-            }
+            None => return Err(GameError::Unowned)
         };
-        // Player can always sell hotel
-        street.asset.borrow_mut().sell_hotel();
-        owner.transact_cash(building_price as i32);
+        if !street.asset().has_hotel() {
+            return Err(GameError::NoHotel);
+        }
+        // The hotel is exchanged for 4 houses from the bank
+        let available = game.houses_left();
+        if available < 4 {
+            return Err(GameError::NotEnoughHouses { needed: 4, available });
+        }
+        game.board[street_idx].asset_mut().sell_hotel();
+        game.players[owner].transact_cash(building_price as i32)
+            .expect("Receiving cash cannot fail");
+        game.emit(GameEvent::SoldHotel {
+            player: owner, square: street_idx, price: building_price });
+        Ok(())
     }
 }
 
-#[derive(Debug)]
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
 pub struct Dice {
     roll: (u32, u32),
     num_rolls: u32, // number of times user has rolled dice
     cumulative_sum: u32
 }
 
+/// Rent the player pays on landing on an owned street
+// Cards that move a player to the nearest station or utility charge a different rent
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rent {
+    Standard,
+    Multiplied(u32), // the standard rent, times this factor
+    DiceTimes(u32)   // the dice roll times this factor, however many utilities are owned
+}
+
+/// What the game is waiting for, before the turn can continue
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TurnPhase {
+    Roll,            // active player must roll the dice (again, after a double)
+    Purchase(usize), // active player landed on the unowned street at this board index
+    Auction(usize),  // the street at this board index is on auction
+    HouseAuction,    // one of the last houses is on auction
+    Actions,         // dice are rolled. Players can trade, until the turn ends
+    GameOver         // the game has ended, and nobody can play anymore
+}
+
+/// When the game ends, other than when only one player remains
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GameMode {
+    Classic,        // until only one player remains
+    TurnLimit(u32), // after this many turns, counting the turn of every player
+    TimeLimit(u64)  // after this many minutes
+}
+
+/// Final position of a player in the game
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Standing {
+    pub player: usize,
+    pub net_worth: u32
+}
+
+/// Income tax the active player landed on, and still chooses how to pay
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TaxChoice {
+    pub square: usize,
+    pub amount: u32, // flat amount of the tax
+    pub share: u32 // percentage of the net worth of the player, when they landed
+}
+
 /// The structure, containing links to all parts of the game
+// The game only holds plain data, and is changed through `&mut self`. This way it can
+// be moved between threads, like the worker threads of the web server.
 pub struct Game {
-    players: Vec<RefCell<player::Player>>,
-    board: [square::Square; BOARD_SIZE as usize],
-    active_player: RefCell<usize>,
-    chance_cards: RefCell<Vec<card::Card>>,
-    community_cards: RefCell<Vec<card::Card>>,
-    is_unit_test: bool
+    players: Vec<player::Player>,
+    board: Vec<square::Square>,
+    active_player: usize,
+    chance_cards: Vec<card::Card>,
+    community_cards: Vec<card::Card>,
+    decisions: Box<dyn decision::DecisionProvider>,
+    phase: TurnPhase,
+    dice: Option<Dice>, // doubles rolled so far in this turn
+    auctions: Vec<usize>, // streets of a bankrupt player the bank still has to auction
+    resume_phase: TurnPhase, // phase the turn goes back to after the bank's auctions
+    bidding: Option<auction::Auction>, // the auction of the street or house on auction
+    house_bid: Option<auction::Bid>, // winning bid on one of the last houses, not built yet
+    trade: Option<trade::Trade>, // proposed trade, waiting for an answer
+    mortgage_choices: Vec<usize>, // mortgaged streets that changed hands, the new owner
+                                  // still has to choose to lift the mortgage or not
+    rent_roll: Option<u32>, // the active player rolls again, and pays the owner of the
+                            // utility they were sent to this many times the roll
+    tax_choice: Option<TaxChoice>, // income tax where the active player still chooses to
+                                   // pay the flat amount or a percentage of their net worth
+    jackpot: u32, // taxes and card payments waiting on Free Parking
+    turn: u32, // number of the current turn, starting at 1
+    deadline: Option<u64>, // end of a timed game, in seconds since the Unix epoch
+    events: Vec<GameEvent>, // events of the command being applied
+    journal: journal::Journal
 }
 
 impl Dice {
@@ -261,14 +326,6 @@ impl Dice {
         }
     }
 
-    /// Zero roll
-    // This should never happen in the game. Its a special case, used when a player
-    // is in trouble and thinks trouble is averted. The game rolls a "0, 0" 
-    // to have the player stay on the same square, but replay the logic of that square
-    fn is_zeros(&self) -> bool {
-        (self.roll.0 == self.roll.1) && (self.roll.0 == 0)
-    }
-
     pub fn total(&self) -> u32 {
         self.roll.0 + self.roll.1
     }
@@ -295,123 +352,516 @@ impl Dice {
 }
 
 impl Game {
-    /// Set unit test flag for the game
-    fn set_unit_test(&mut self) {
-        self.is_unit_test = true;
+    /// Replace the source of player decisions (the terminal, by default)
+    pub fn set_decision_provider(&mut self,
+                                 decisions: Box<dyn decision::DecisionProvider>) {
+        self.decisions = decisions;
+    }
+
+    /// Ask the players a yes/no question
+    fn yes_no(&mut self, message: &str) -> bool {
+        self.decisions.yes_no(message)
     }
 
-    pub fn board(&self) -> &[square::Square; BOARD_SIZE as usize] {
+    /// Ask the players to select one of the players in the game
+    fn select_player(&mut self, exclude: Option<usize>, msg: &str) -> Option<usize> {
+        self.decisions.player_idx(&self.players, exclude, msg)
+    }
+
+    pub fn board(&self) -> &[square::Square] {
         &self.board
     }
 
-    pub fn players(&self) -> &Vec<RefCell<player::Player>> {
+    pub fn players(&self) -> &Vec<player::Player> {
         &self.players
     }
 
     /// The index in the player list of the currently active player
     // This is a reference to the list of players, not the player itself
     pub fn active_player(&self) -> usize {
-        *self.active_player.borrow()
+        self.active_player
+    }
+
+    fn set_active_player(&mut self, p_idx: usize) {
+        self.active_player = p_idx;
     }
 
-    fn set_active_player(&self, p_idx: usize) {
-        self.active_player.replace(p_idx);
+    /// What the game is waiting for
+    pub fn phase(&self) -> TurnPhase {
+        self.phase
+    }
+
+    fn set_phase(&mut self, phase: TurnPhase) {
+        self.phase = phase;
+    }
+
+    /// How the game ends
+    pub fn mode(&self) -> GameMode {
+        self.journal.mode()
+    }
+
+    /// House rules of the game
+    pub fn rules(&self) -> rules::Rules {
+        self.journal.rules()
+    }
+
+    /// Number of the current turn, starting at 1
+    pub fn turn(&self) -> u32 {
+        self.turn
+    }
+
+    /// Seconds until the end of a timed game
+    pub fn seconds_left(&self) -> Option<u64> {
+        self.deadline.map(|d| d.saturating_sub(now()))
+    }
+
+    /// Check if the time of a timed game has run out
+    fn time_is_up(&self) -> bool {
+        self.phase() != TurnPhase::GameOver && self.seconds_left() == Some(0)
+    }
+
+    /// Everything that happened in the game so far
+    pub fn journal(&self) -> &journal::Journal {
+        &self.journal
+    }
+
+    /// Record an event of the command being applied
+    fn emit(&mut self, event: GameEvent) {
+        self.events.push(event);
     }
 
     /// Set the next player to be active
-    fn next_player(&self) {
-        for _ in 1..self.players.len() {
-            let p_idx = (*self.active_player.borrow() + 1) % self.players.len();
-            if self.players.get(p_idx).unwrap().borrow().left_game() {
+    // The game is over when only one player remains, or nobody in a game for one.
+    // A game with a turn limit is also over after the last turn.
+    fn next_player(&mut self) {
+        let remaining = self.players.iter().filter(|p| !p.left_game()).count();
+        let last_turn = match self.mode() {
+            GameMode::TurnLimit(t) => self.turn >= t,
+            _ => false
+        };
+        if remaining == 0 || (remaining == 1 && self.players.len() > 1) || last_turn {
+            self.finish_game();
+            return;
+        }
+        for i in 1..=self.players.len() {
+            let p_idx = (self.active_player() + i) % self.players.len();
+            if self.players[p_idx].left_game() {
                 continue;
             }
             self.set_active_player(p_idx);
+            self.start_turn();
             return; // next active player set
         }
-        panic!("No players left in the game");
     }
 
-    /// Start the game for the command line interface
-    pub fn start(self) {
-        loop {
-            let p_ref = self.players.get(self.active_player()).unwrap();
-            if p_ref.borrow().left_game() {
-                return;
-            }
-            println!("\n=== {}, Your turn ===", p_ref.borrow().name());
-            self.jail_time();
+    /// End the game, and announce the winner
+    fn finish_game(&mut self) {
+        self.set_phase(TurnPhase::GameOver);
+        self.auctions.clear();
+        self.bidding = None;
+        let standings = self.standings();
+        self.emit(GameEvent::GameOver { winner: standings[0].player, standings });
+    }
 
-            print!("Roll dice: ");
-            self.execute_turn(dialog::capture_dice_roll());
+    /// Value of everything a player owns, less their debts
+    // Streets count for their price, or half of it when mortgaged. Buildings count
+    // for what they cost.
+    pub fn net_worth(&self, p_idx: usize) -> u32 {
+        let player = &self.players[p_idx];
+        if player.left_game() {
+            return 0;
+        }
+        self.worth(p_idx, true).saturating_sub(player.debt_total())
+    }
 
-            if self.is_unit_test {
-                return;
-            }
-            // present options of other transactions user can make
+    /// Cash of a player, and the price of their streets and buildings
+    // The income tax counts mortgaged streets at their printed price
+    fn worth(&self, p_idx: usize, halve_mortgaged: bool) -> u32 {
+        let assets: u32 = self.board.iter()
+            .filter(|s| s.asset().owner() == Some(p_idx))
+            .map(|s| {
+                let a = s.asset();
+                let price = match a.is_mortgaged() && halve_mortgaged {
+                    true  => s.get_price() / 2,
+                    false => s.get_price()
+                };
+                let buildings = a.house_num() + if a.has_hotel() { 1 } else { 0 };
+                match s.get_street_details().and_then(|sd| sd.get_suburb()) {
+                    Some(suburb) => price + buildings * suburb.building_price(),
+                    None         => price
+                }
+            })
+            .sum();
+        self.players[p_idx].cash() + assets
+    }
 
-            self.get_out_of_trouble();
-            self.execute_user_action();
-            self.next_player();
-            // TODO: We need an elegant way to end the game,
-            // otherwise next_player will panic
-        }
+    /// Houses the bank still has
+    // A hotel street keeps its 4 houses on the board, but the houses went back to
+    // the bank when the hotel was bought
+    pub fn houses_left(&self) -> u32 {
+        let used: u32 = self.board.iter()
+            .map(|s| s.asset())
+            .filter(|a| !a.has_hotel())
+            .map(|a| a.house_num())
+            .sum();
+        HOUSES.saturating_sub(used)
     }
 
-    /// Complete a turn from the web client
-    pub fn go(&self, mut dice: Dice) {
-        // FIXME Ensure no players are in trouble
+    /// Hotels the bank still has
+    pub fn hotels_left(&self) -> u32 {
+        let used = self.board.iter().filter(|s| s.asset().has_hotel()).count() as u32;
+        HOTELS.saturating_sub(used)
+    }
 
-        // FIXME: Change method to pass in player throwing the dice
-        // For now, we just move to the next player
+    /// Check if more players can build than the bank has houses for
+    // The remaining houses then go to the highest bidder
+    pub fn house_shortage(&self) -> bool {
+        let mut builders = self.house_sites().iter()
+            .map(|&(owner, _)| owner)
+            .collect::<Vec<usize>>();
+        builders.sort();
+        builders.dedup();
+        let houses = self.houses_left();
+        houses > 0 && builders.len() > 1 && builders.len() as u32 > houses
+    }
 
-        // FIXME: This will need some work to cater for the web client
-        let p_ref = self.players.get(self.active_player()).unwrap();
-        if p_ref.borrow().left_game() {
-            return;
+    /// Owner and building price of every street where a house can be built
+    fn house_sites(&self) -> Vec<(usize, u32)> {
+        self.board.iter()
+            .filter(|s| !s.asset().has_hotel() && s.asset().house_num() < 4)
+            .filter_map(|s| {
+                let suburb = s.get_street_details().and_then(|sd| sd.get_suburb())?;
+                match s.asset().owner() {
+                    Some(o) if self.player_owns_suburb(o, s)
+                        && self.street_eligible_for_house(s) => Some((o, suburb.building_price())),
+                    _ => None
+                }
+            })
+            .collect()
+    }
+
+    /// Players ranked by their net worth. Players who left the game come last
+    pub fn standings(&self) -> Vec<Standing> {
+        let mut standings = (0..self.players.len())
+            .map(|p| Standing { player: p, net_worth: self.net_worth(p) })
+            .collect::<Vec<Standing>>();
+        standings.sort_by_key(|s| (self.players[s.player].left_game(),
+                                   std::cmp::Reverse(s.net_worth)));
+        standings
+    }
+
+    /// Bids on the street on auction
+    pub fn auction(&self) -> Option<&auction::Auction> {
+        self.bidding.as_ref()
+    }
+
+    /// Mortgaged streets that changed hands, where the new owner still has to
+    /// choose to lift the mortgage now or later
+    pub fn mortgage_choices(&self) -> &[usize] {
+        &self.mortgage_choices
+    }
+
+    /// Income tax where the active player still chooses how to pay
+    pub fn tax_choice(&self) -> Option<TaxChoice> {
+        self.tax_choice
+    }
+
+    /// Taxes and card payments, for the next player landing on Free Parking
+    pub fn jackpot(&self) -> u32 {
+        self.jackpot
+    }
+
+    /// Trade waiting for an answer of the counterparty
+    pub fn trade(&self) -> Option<&trade::Trade> {
+        self.trade.as_ref()
+    }
+
+    /// The player who won the game, once the game is over
+    pub fn winner(&self) -> Option<usize> {
+        match self.phase() {
+            TurnPhase::GameOver => self.standings().first().map(|s| s.player),
+            _ => None
         }
-        println!("\n=== {}, Your turn ===", p_ref.borrow().name());
-        self.jail_time();
+    }
 
-        self.execute_turn(dice);
+    /// Prepare the turn of the active player
+    fn start_turn(&mut self) {
+        self.set_phase(TurnPhase::Roll);
+        self.dice = None;
+        self.turn += 1;
+        self.emit(GameEvent::TurnStarted { player: self.active_player() });
+    }
 
-        // present options of other transactions user can make
+    /// Capture the complete state of the game
+    pub fn save(&self) -> save::SavedGame {
+        save::SavedGame {
+            version: save::VERSION,
+            players: self.players.clone(),
+            assets: self.board.iter().map(|s| s.asset().clone()).collect(),
+            active_player: self.active_player(),
+            chance_cards: self.chance_cards.clone(),
+            community_cards: self.community_cards.clone(),
+            phase: self.phase(),
+            dice: self.dice.clone(),
+            auctions: self.auctions.clone(),
+            resume_phase: self.resume_phase,
+            bidding: self.bidding.clone(),
+            house_bid: self.house_bid,
+            trade: self.trade.clone(),
+            mortgage_choices: self.mortgage_choices.clone(),
+            rent_roll: self.rent_roll,
+            tax_choice: self.tax_choice,
+            jackpot: self.jackpot,
+            turn: self.turn,
+            deadline: self.deadline,
+            journal: self.journal.clone()
+        }
+    }
 
-        // FIXME: web client should be able to support actions, and getting
-        // player out of trouble
-        self.get_out_of_trouble();
-        self.execute_user_action();
-        self.next_player();
-        // TODO: We need an elegant way to end the game,
-        // otherwise next_player will panic
+    /// Create a game from its saved state
+    pub fn restore(saved: save::SavedGame) -> Result<Game, String> {
+        saved.journal.edition().validate()?;
+        let mut board = saved.journal.edition().squares();
+        if saved.assets.len() != board.len() {
+            return Err(format!("Expected {} squares, but found {}",
+                               board.len(), saved.assets.len()));
+        }
+        saved.journal.decks().fit_board(board.len())?;
+        card::Decks { chance: saved.chance_cards.clone(),
+                      community_chest: saved.community_cards.clone() }
+            .fit_board(board.len())?;
+        if saved.active_player >= saved.players.len() {
+            return Err(String::from("The active player isn't in the game"));
+        }
+        for (i, p) in saved.players.iter().enumerate() {
+            if p.turn_idx() != i || p.position() >= board.len() {
+                return Err(format!("Invalid details for player {}", p.name()));
+            }
+        }
+        if saved.assets.iter().any(|a| match a.owner() {
+                Some(o) => o >= saved.players.len(),
+                None    => false
+            }) {
+            return Err(String::from("A street is owned by a player who isn't in the game"));
+        }
+
+        for (square, asset) in board.iter_mut().zip(saved.assets) {
+            *square.asset_mut() = asset;
+        }
+        Ok(Game {
+            players: saved.players,
+            board,
+            active_player: saved.active_player,
+            chance_cards: saved.chance_cards,
+            community_cards: saved.community_cards,
+            decisions: Box::new(decision::TerminalDecisions),
+            phase: saved.phase,
+            dice: saved.dice,
+            auctions: saved.auctions,
+            resume_phase: saved.resume_phase,
+            bidding: saved.bidding,
+            house_bid: saved.house_bid,
+            trade: saved.trade,
+            mortgage_choices: saved.mortgage_choices,
+            rent_roll: saved.rent_roll,
+            tax_choice: saved.tax_choice,
+            jackpot: saved.jackpot,
+            turn: saved.turn,
+            deadline: saved.deadline,
+            events: Vec::new(),
+            journal: saved.journal
+        })
     }
 
-    fn get_out_of_trouble(&self) {
-        let p_ref = self.players.get(self.active_player()).unwrap();
-        let is_in_trouble;
-        let turn_idx;
-        {
-            let player = p_ref.borrow();
-            is_in_trouble = player.is_in_trouble();
-            turn_idx = player.turn_idx();
+    /// Execute a command, and return everything that happened as a result
+    // A command that isn't allowed leaves the game unchanged, and returns a
+    // `Rejected` event with the reason. Executed commands are added to the journal.
+    // Once the time of a timed game is up, the next command ends the game instead.
+    pub fn apply(&mut self, command: GameCommand) -> Vec<GameEvent> {
+        let command = match command {
+            GameCommand::Undo | GameCommand::UndoTurn | GameCommand::Redo => command,
+            _ if self.time_is_up() => GameCommand::EndGame,
+            _ => command
+        };
+        let r = match command.clone() {
+            GameCommand::Undo     => return self.undo(false),
+            GameCommand::UndoTurn => return self.undo(true),
+            GameCommand::Redo     => return self.redo(),
+            _ if self.phase() == TurnPhase::GameOver => Err(GameError::GameOver),
+            GameCommand::RollDice(d1, d2)   => self.roll_dice(Dice::new(d1, d2)),
+            GameCommand::BuyProperty        => self.accept_purchase(),
+            GameCommand::DeclinePurchase    => self.decline_purchase(),
+            GameCommand::Bid { amount }     => self.place_bid(amount),
+            GameCommand::PassBid            => self.pass_bid(),
+            GameCommand::BuyHouse { street }   => self.street_action(street, actions::buy_house),
+            GameCommand::AuctionHouse => self.auction_house(),
+            GameCommand::SellHouse { street }  => self.street_action(street, actions::sell_house),
+            GameCommand::BuyHotel { street }   => self.street_action(street, actions::buy_hotel),
+            GameCommand::SellHotel { street }  => self.street_action(street, actions::sell_hotel),
+            GameCommand::Mortgage { street }   =>
+                self.street_action(street, actions::mortgage_street),
+            GameCommand::Unmortgage { street } =>
+                self.street_action(street, actions::unmortgage_street),
+            GameCommand::KeepMortgage { street } => self.keep_mortgage(street),
+            GameCommand::PayIncomeTax { net_worth } => self.pay_income_tax(net_worth),
+            GameCommand::SellStreet { street, buyer, price } =>
+                self.sell_street(street, buyer, price),
+            GameCommand::ProposeTrade { trade } => self.propose_trade(trade),
+            GameCommand::AcceptTrade => self.accept_trade(),
+            GameCommand::RejectTrade => self.reject_trade(),
+            GameCommand::CounterTrade { gives, receives } => self.counter_trade(gives, receives),
+            GameCommand::PayBail   => self.pay_bail(),
+            GameCommand::UseJailCard => self.use_jail_card(),
+            GameCommand::EndTurn   => self.end_turn(),
+            GameCommand::LeaveGame => self.leave_game(),
+            GameCommand::GoBankrupt { player } => self.go_bankrupt(player),
+            GameCommand::EndGame   => self.end_game()
+        };
+        if let Err(error) = r {
+            self.emit(GameEvent::Rejected { error });
+            return std::mem::take(&mut self.events);
         }
+        self.collect_debts();
+        let events = std::mem::take(&mut self.events);
+        self.journal.record(command, events.clone());
+        events
+    }
 
-        while is_in_trouble {
-            // player couldn't pay for the turn.
-            // Player must now sell assets for cash
-            println!("Uh oh! You don't have enough money to continue.");
-            println!("You can sell assets, or leave the game");
+    /// Take back the last command, or all commands of the turn
+    // The game is replayed from the journal without the undone commands, so the
+    // order of the cards and the doubles rolled are restored as well.
+    fn undo(&mut self, whole_turn: bool) -> Vec<GameEvent> {
+        let mut journal = self.journal.clone();
+        let mut undone = Vec::new();
+        while let Some(entry) = journal.undo() {
+            undone.push(GameEvent::Undone { command: entry.command });
+            let turn_started = match journal.entries().last() {
+                None    => true,
+                Some(e) => e.events.iter()
+                    .any(|e| matches!(e, GameEvent::TurnStarted { .. }))
+            };
+            if !whole_turn || turn_started {
+                break;
+            }
+        }
+        if undone.is_empty() {
+            return vec![GameEvent::Rejected { error: GameError::NothingToUndo }];
+        }
+        self.replay(journal, undone)
+    }
 
-            self.execute_user_action();
+    /// Execute the last command that was taken back again
+    fn redo(&mut self) -> Vec<GameEvent> {
+        let mut journal = self.journal.clone();
+        let entry = match journal.redo() {
+            Some(e) => e,
+            None    => return vec![
+                GameEvent::Rejected { error: GameError::NothingToRedo }]
+        };
+        let mut events = vec![GameEvent::Redone { command: entry.command }];
+        events.extend(entry.events);
+        self.replay(journal, events)
+    }
+
+    /// Replace the state of the game, by replaying the journal
+    fn replay(&mut self, journal: journal::Journal, events: Vec<GameEvent>)
+            -> Vec<GameEvent> {
+        let saved = match journal.replay() {
+            Ok(g)  => g.save(),
+            Err(error) => return vec![GameEvent::Rejected { error }]
+        };
+        self.players = saved.players;
+        for (square, asset) in self.board.iter_mut().zip(saved.assets) {
+            *square.asset_mut() = asset;
+        }
+        self.set_active_player(saved.active_player);
+        self.chance_cards = saved.chance_cards;
+        self.community_cards = saved.community_cards;
+        self.set_phase(saved.phase);
+        self.dice = saved.dice;
+        self.auctions = saved.auctions;
+        self.resume_phase = saved.resume_phase;
+        self.bidding = saved.bidding;
+        self.house_bid = saved.house_bid;
+        self.trade = saved.trade;
+        self.mortgage_choices = saved.mortgage_choices;
+        self.rent_roll = saved.rent_roll;
+        self.tax_choice = saved.tax_choice;
+        self.jackpot = saved.jackpot;
+        self.turn = saved.turn;
+        self.journal = journal; // the deadline of a timed game stays the same
+        events
+    }
+
+    /// Start the game for the command line interface
+    // Asks the decision provider for the next command, and prints what happened
+    pub fn start(mut self) {
+        println!("{}", GameEvent::TurnStarted { player: self.active_player() }
+                 .describe(&self));
+        loop {
+            let player = &self.players[self.active_player()];
+            let command = match self.phase() {
+                TurnPhase::GameOver => {
+                    publisher::publish(&self);
+                    return;
+                },
+                _ if !self.mortgage_choices.is_empty() => {
+                    let street = self.mortgage_choices[0];
+                    self.capture_mortgage_choice(street)
+                },
+                _ if self.rent_roll.is_some() => {
+                    print!("Roll dice for the rent: ");
+                    let (d1, d2) = self.decisions.roll_dice().roll();
+                    GameCommand::RollDice(d1, d2)
+                },
+                _ if self.tax_choice.is_some() => self.capture_tax_choice(),
+                TurnPhase::Roll => {
+                    let is_in_jail = player.is_in_jail();
+                    let has_card = player.num_get_out_of_jail_cards() > 0;
+                    if is_in_jail && has_card
+                            && self.yes_no("Use your get-out-of-jail-free card?") {
+                        GameCommand::UseJailCard
+                    } else if is_in_jail && self.yes_no(&format!(
+                            "Bribe the guards ${} to get out of jail?", self.rules().bail)) {
+                        GameCommand::PayBail
+                    } else {
+                        print!("Roll dice: ");
+                        let (d1, d2) = self.decisions.roll_dice().roll();
+                        GameCommand::RollDice(d1, d2)
+                    }
+                },
+                TurnPhase::Purchase(square_idx) => {
+                    let square = &self.board[square_idx];
+                    let message = format!("Do you want to buy {} for ${}?",
+                                          square.name(), square.get_price());
+                    match self.yes_no(&message) {
+                        true  => GameCommand::BuyProperty,
+                        false => GameCommand::DeclinePurchase
+                    }
+                },
+                TurnPhase::Auction(_) | TurnPhase::HouseAuction => self.capture_auction(),
+                TurnPhase::Actions if self.trade.is_some() => {
+                    let trade = self.trade.clone().expect("Trade should exist");
+                    self.capture_trade_response(trade)
+                },
+                TurnPhase::Actions => {
+                    // present options of other transactions user can make
+                    publisher::publish(&self);
+                    match self.capture_user_action() {
+                        Some(c) => c,
+                        None    => {
+                            println!("Back to the menu");
+                            continue;
+                        }
+                    }
+                }
+            };
 
-            // player still in the game, lets try run this turn again
-            if p_ref.borrow().left_game() {
-                // TODO: Distribute cash (or in leave_game method)
-                break; // player has exited game
+            for event in self.apply(command) {
+                println!("{}", event.describe(&self));
             }
-            // stay on same square, to see if player can pay debts
-            self.execute_turn(Dice::new(0, 0));
-            if !p_ref.borrow().is_in_trouble() {
-                break; // out of trouble
+            if let Err(e) = save::save_to_file(&self, save::AUTOSAVE_FILE) {
+                println!("{}", e);
             }
         }
     }
@@ -419,18 +869,25 @@ impl Game {
     /// Calculate rent. If the square is unowned, there is no rent
     // Calculate rent, taking into account if a player owns all streets, and the number of
     // properties on the street.
-    fn calculate_rent(&self, s: &square::Square, dice: Dice) -> Option<u32> {
-        let owner = match s.asset.borrow().owner() {
+    fn calculate_rent(&self, s: &square::Square, dice: Dice, rent: Rent) -> Option<u32> {
+        let owner = match s.asset().owner() {
             None => {
                 // Nobody owns this square
                 return None;
             },
             Some(r) => r
         };
-        if s.asset.borrow().is_mortgaged() {
+        if s.asset().is_mortgaged() {
             return None;
         };
 
+        match rent {
+            Rent::Standard      => {},
+            Rent::Multiplied(m) => return self.calculate_rent(s, dice, Rent::Standard)
+                                       .map(|r| r * m),
+            Rent::DiceTimes(m)  => return Some(dice.cumulative_sum * m)
+        };
+
         // Need owner of this square
         // get all squares owner owns of the same type
         let rent: u32 = match s.square_type() {
@@ -439,11 +896,9 @@ impl Game {
                 let utility_num = owned_squares.into_iter()
                     .filter(|&x| x.square_type() == square::SquareType::Utility)
                     .collect::<Vec<&square::Square>>().len();
-                let utility_num = utility_num as u32;
-                match utility_num {
-                    1 => (dice.cumulative_sum * 4) as u32,
-                    2 => (dice.cumulative_sum * 10) as u32,
-                    _ => 0 // Error, no rent
+                match self.journal.edition().utility_rent.get(utility_num - 1) {
+                    Some(multiplier) => dice.cumulative_sum * multiplier,
+                    None             => 0 // Error, no rent
                 }
             },
             square::SquareType::Station => {
@@ -452,14 +907,9 @@ impl Game {
                 let station_num = owned_squares.into_iter()
                     .filter(|&x| x.square_type() == square::SquareType::Station)
                     .collect::<Vec<&square::Square>>().len();
-                let station_num = station_num as u32;
-
-                match station_num {
-                    1 => 25,
-                    2 => 50,
-                    3 => 100, // $100 for 3 stations
-                    4 => 200,  // $200 for 4 stations
-                    _ => 0 // Error, no rent 
+                match self.journal.edition().station_rent.get(station_num - 1) {
+                    Some(&rent) => rent,
+                    None        => 0 // Error, no rent
                 }
             },
             square::SquareType::Street => {
@@ -467,7 +917,7 @@ impl Game {
                 if !self.player_owns_suburb(owner, s) {
                     return Some(street_details.rent());
                 }
-                let a = s.asset.borrow();
+                let a = s.asset();
                 if a.has_hotel() {
                     return Some(street_details.rent_suburb()[5]);
                 }
@@ -488,440 +938,1036 @@ impl Game {
         Some(rent)
     }
 
-    /// Give player in jail options to get out
-    fn jail_time(&self) {
-        let mut player = self.players.get(self.active_player()).unwrap().borrow_mut();
+    /// Active player in jail tries to roll a double, returning if they are free
+    // A double releases the player. After the third roll without a double, the
+    // player has to pay the bail, and moves anyway.
+    fn jail_time(&mut self, dice: &Dice) -> bool {
+        let p_idx = self.active_player();
+        if dice.is_double() {
+            self.players[p_idx].leave_jail();
+            self.emit(GameEvent::LeftJail { player: p_idx, release: JailRelease::Double });
+            return true;
+        }
+        let turns = self.players[p_idx].serve_jail_turn();
+        if turns < JAIL_TURNS {
+            self.emit(GameEvent::ServedJailTurn { player: p_idx, turns });
+            return false;
+        }
+        let bail = self.rules().bail;
+        self.players[p_idx].leave_jail();
+        self.emit(GameEvent::LeftJail {
+            player: p_idx, release: JailRelease::TimeServed { bail } });
+        self.pay(p_idx, bail, player::Creditor::Bank, player::DebtReason::Bail);
+        true
+    }
+
+    /// Active player uses a get-out-of-jail-free card, before rolling the dice
+    fn use_jail_card(&mut self) -> Result<(), GameError> {
+        if self.phase() != TurnPhase::Roll {
+            return Err(GameError::DiceAlreadyRolled);
+        }
+        let p_idx = self.active_player();
+        let player = &mut self.players[p_idx];
         if !player.is_in_jail() {
-            return;
+            return Err(GameError::NotInJail);
         }
-        if player.redeem_jail_free_card().is_ok() {
-            println!("Yay, No More Jail, thanks to your get-out-of-jail-free card");
-            return;
-        };
-        let pay_cash = match self.is_unit_test {
-            true  => true,
-            false => dialog::yes_no(
-                "Bribe the guards $50 to get out of jail?")
+        let (deck, card) = player.redeem_jail_free_card()?;
+        self.deck_mut(deck).push(card);
+        self.emit(GameEvent::LeftJail { player: p_idx, release: JailRelease::Card });
+        Ok(())
+    }
+
+    /// Active player bribes the guards to get out of jail, before rolling the dice
+    fn pay_bail(&mut self) -> Result<(), GameError> {
+        if self.phase() != TurnPhase::Roll {
+            return Err(GameError::DiceAlreadyRolled);
+        }
+        let p_idx = self.active_player();
+        let bail = self.rules().bail;
+        let player = &mut self.players[p_idx];
+        if !player.is_in_jail() {
+            return Err(GameError::NotInJail);
+        }
+        player.bribe_guards(bail)?;
+        self.emit(GameEvent::LeftJail {
+            player: p_idx, release: JailRelease::Bail { amount: bail } });
+        Ok(())
+    }
+
+    /// Roll the dice for the active player
+    // After a double, the player rolls again. The player then moves the sum of all
+    // the rolls.
+    fn roll_dice(&mut self, roll: Dice) -> Result<(), GameError> {
+        if self.phase() != TurnPhase::Roll && self.rent_roll.is_none() {
+            return Err(GameError::DiceAlreadyRolled);
+        }
+        let (d1, d2) = roll.roll();
+        if !(1..=6).contains(&d1) || !(1..=6).contains(&d2) {
+            return Err(GameError::InvalidDice);
+        }
+        if let Some(factor) = self.rent_roll.take() {
+            self.pay_rent(roll, Rent::DiceTimes(factor));
+            return Ok(());
+        }
+
+        let dice = match self.dice.take() {
+            Some(mut d) => {
+                d.reroll(roll);
+                d
+            },
+            None => roll
         };
-        if pay_cash {
-            player.bribe_guards();
+
+        let is_in_jail = self.players[self.active_player()].is_in_jail();
+        if dice.is_double() && !is_in_jail && dice.num_rolls < 3 {
+            self.emit(GameEvent::RolledDouble { player: self.active_player() });
+            self.dice = Some(dice);
+            return Ok(());
         }
+
+        self.set_phase(TurnPhase::Actions);
+        self.execute_turn(dice);
+        Ok(())
     }
 
-    /// Execute user's menu selection
-    fn execute_user_action(&self) {
-        let p_ref = self.players.get(self.active_player()).unwrap();
-        let is_in_trouble;
-        let turn_idx;
-        {
-            let player = p_ref.borrow();
-            is_in_trouble = player.is_in_trouble();
-            turn_idx = player.turn_idx();
+    /// End the turn of the active player
+    // A player in trouble has to pay their debts first
+    fn end_turn(&mut self) -> Result<(), GameError> {
+        match self.phase() {
+            TurnPhase::Roll => return Err(GameError::DiceNotRolled),
+            TurnPhase::Purchase(_) | TurnPhase::Auction(_) | TurnPhase::HouseAuction => {
+                return Err(GameError::PurchasePending);
+            },
+            TurnPhase::Actions => {},
+            TurnPhase::GameOver => return Err(GameError::GameOver)
+        };
+
+        let debt = self.players[self.active_player()].debt_total();
+        if debt > 0 {
+            // the player can sell assets, or leave the game
+            return Err(GameError::OutstandingDebt { amount: debt });
         }
-        loop {
-            publisher::publish(&self);
-            let option = match is_in_trouble {
-                true  => dialog::trouble_user_actions(),
-                false => dialog::additional_user_actions()
-            };
-            match option {
-                dialog::UserAction::EndGame => {
-                    // liquify assets
-                    let squares = self.board.iter()
-                        .filter(|s| { match s.asset.borrow().owner() {
-                                None => false,
-                                Some(u) => u == turn_idx
-                            }
-                        })
-                        .collect::<Vec<&square::Square>>();
-                    for square in squares {
-                        square.asset.borrow_mut().liquify();
-                    }
+        if let Some(player) = self.debtor() {
+            // someone who paid outside their turn
+            return Err(GameError::PlayerInTrouble { player });
+        }
+        if !self.mortgage_choices.is_empty() {
+            return Err(GameError::MortgageChoicePending);
+        }
+        if self.rent_roll.is_some() {
+            return Err(GameError::RentRollPending);
+        }
+        if self.house_bid.is_some() {
+            return Err(GameError::HouseBidPending);
+        }
+        if self.tax_choice.is_some() {
+            return Err(GameError::TaxChoicePending);
+        }
+        self.next_player();
+        Ok(())
+    }
 
-                    let mut player = self.players.get(turn_idx).unwrap().borrow_mut();
-                    let pos_idx = player.position();
-                    player.leave_game();
-
-                    // If player is on a card square, move the card to the bottom
-                    let current_square = &self.board[pos_idx];
-                    match current_square.square_type() {
-                        square::SquareType::CommunityCard => {
-                            let mut cards = self.community_cards.borrow_mut();
-                            let card = cards.remove(0);
-                            cards.push(card);
-                        },
-                        square::SquareType::ChanceCard    => {
-                            let mut cards = self.chance_cards.borrow_mut();
-                            let card = cards.remove(0);
-                            cards.push(card);
-                        },
-                        _ => {} // do nothing
-                    };
-
-                    println!("== Game Over! {} ==", player.name());
-                    break;
-                },
-                dialog::UserAction::EndTurn => return,
-                dialog::UserAction::SellStreet => {
-                    let owner_idx = match is_in_trouble {
-                        true  => turn_idx,
-                        false => {
-                            match dialog::get_player_idx(
-                                    self, None, "Select the current owner") {
-                                Ok(s)  => s,
-                                Err(_) => {
-                                    println!("Back to the menu");
-                                    continue;
-                                }
-                            }
-                        }
-                    };
-
-                    let eligible_streets :Vec<(usize, &square::Square)> = 
-                            self.board.iter().enumerate()
-                        .filter(|(_, s)| { match s.asset.borrow().owner() {
-                                None => false,
-                                Some(u) => u == owner_idx
-                            }
-                        })
-                        .collect();
-                    let street_idx = match dialog::get_street(eligible_streets) {
-                        Ok(s)  => s,
-                        Err(_) => {
-                            println!("Back to the menu");
-                            continue;
-                        }
-                    };
-                    let purchaser_idx = match dialog::get_player_idx(
-                            self, Some(owner_idx), "Select the new owner") {
-                        Ok(s)  => s,
-                        Err(_) => {
-                            println!("Back to the menu");
-                            continue;
-                        }
-                    };
-                    let mut orig_owner = self.players.get(owner_idx)
-                            .unwrap().borrow_mut();
+    /// Active player leaves the game
+    // A player in trouble is bankrupt, and hands everything over to their creditor.
+    // Without debts, the assets go back to the bank.
+    fn leave_game(&mut self) -> Result<(), GameError> {
+        let turn_idx = self.active_player();
+        self.retire(turn_idx);
+        self.rent_roll = None;
+        self.tax_choice = None;
+        self.bidding = None;
+        self.next_player();
+        self.start_queued_auctions();
+        Ok(())
+    }
 
+    /// A player in trouble goes bankrupt
+    // Outside their turn, the player only owes the active player, who carries on
+    // with their turn
+    fn go_bankrupt(&mut self, p_idx: usize) -> Result<(), GameError> {
+        let player = self.players.get(p_idx).ok_or(GameError::NoSuchPlayer)?;
+        if player.left_game() {
+            return Err(GameError::PlayerLeftGame { player: p_idx });
+        }
+        if !player.is_in_trouble() {
+            return Err(GameError::NotInTrouble);
+        }
+        if p_idx == self.active_player() {
+            return self.leave_game();
+        }
+        match self.phase() {
+            TurnPhase::Purchase(_) | TurnPhase::Auction(_) | TurnPhase::HouseAuction =>
+                return Err(GameError::PurchasePending),
+            _ => {}
+        };
+        self.retire(p_idx);
+        if self.players.iter().filter(|p| !p.left_game()).count() == 1 {
+            self.finish_game();
+        } else {
+            self.start_queued_auctions();
+        }
+        Ok(())
+    }
 
-                    let mut new_owner = self.players.get(purchaser_idx)
-                                        .unwrap().borrow_mut();
+    /// Player leaves the game, and hands over their assets
+    // With several debts, the first creditor receives everything, including what
+    // other players still owe the leaving player
+    fn retire(&mut self, p_idx: usize) {
+        let creditor = match self.players[p_idx].debts().first().map(|d| d.creditor) {
+            Some(c) => {
+                self.emit(GameEvent::WentBankrupt { player: p_idx, creditor: c });
+                c
+            },
+            None => player::Creditor::Bank
+        };
+        self.hand_over_assets(p_idx, creditor);
+        self.players[p_idx].leave_game();
+        for q_idx in 0..self.players.len() {
+            let in_trouble = self.players[q_idx].is_in_trouble();
+            self.players[q_idx].transfer_debts(player::Creditor::Player(p_idx), creditor, q_idx);
+            if in_trouble && !self.players[q_idx].is_in_trouble() {
+                self.emit(GameEvent::OutOfTrouble { player: q_idx });
+            }
+        }
+        if self.house_bid.map(|b| b.player) == Some(p_idx) {
+            self.house_bid = None;
+        }
+        if let Some(t) = &self.trade {
+            if t.proposer == p_idx || t.counterparty == p_idx {
+                self.trade = None;
+            }
+        }
+        self.emit(GameEvent::LeftGame { player: p_idx });
+    }
 
+    /// Player in trouble who isn't taking their turn
+    fn debtor(&self) -> Option<usize> {
+        (0..self.players.len())
+            .find(|&p| p != self.active_player() && self.players[p].is_in_trouble())
+    }
 
-                    let purchase_price = match dialog::get_amount() {
-                        Ok(s)  => s,
-                        Err(_) => {
-                            println!("Back to the menu");
-                            continue;
-                        }
-                    };
+    /// All players agree to stop playing. The richest player wins
+    fn end_game(&mut self) -> Result<(), GameError> {
+        self.finish_game();
+        Ok(())
+    }
 
-                    actions::sell_street(&self, &mut orig_owner, &mut new_owner,
-                                         street_idx, purchase_price);
-                },
-                dialog::UserAction::BuyHouse => {
-                    let player_idx = match dialog::get_player_idx(
-                            self, None, "Select the owner") {
-                        Ok(s)  => s,
-                        Err(_) => {
-                            println!("Back to the menu");
-                            continue;
-                        }
-                    };
-
-                    let mut owner = self.players.get(player_idx)
-                                        .unwrap().borrow_mut();
-
-                    let eligible_streets :Vec<(usize, &square::Square)> = 
-                            self.board.iter().enumerate()
-                        .filter(|(_, s)| { match s.asset.borrow().owner() {
-                                None => false,
-                                Some(u) => u == owner.turn_idx()
-                            }
-                        })
-                        .filter(|(_, s)| s.asset.borrow().house_num() < 4 )
-                        .collect();
-                    let street_idx = match dialog::get_street(eligible_streets) {
-                        Ok(s)  => s,
-                        Err(_) => {
-                            println!("Back to the menu");
-                            continue;
-                        }
-                    };
+    /// Hand over everything of value of a player to their creditor
+    // Buildings are sold to the bank first. A creditor player receives the cash, the
+    // streets and the jail cards, and pays 10% interest on mortgaged streets. When the
+    // bank is the creditor, it auctions the streets instead.
+    fn hand_over_assets(&mut self, p_idx: usize, creditor: player::Creditor) {
+        for square_idx in 0..self.board.len() {
+            let square = &self.board[square_idx];
+            if square.asset().owner() != Some(p_idx) {
+                continue;
+            }
+            let price = match square.get_street_details().and_then(|sd| sd.get_suburb()) {
+                Some(s) => s.building_price(),
+                None    => continue
+            };
+            if square.asset().has_hotel() {
+                self.board[square_idx].asset_mut().sell_hotel();
+                self.players[p_idx].transact_cash(price as i32)
+                    .expect("Receiving cash cannot fail");
+                self.emit(GameEvent::SoldHotel { player: p_idx, square: square_idx, price });
+            }
+            while self.board[square_idx].asset_mut().sell_house().is_ok() {
+                self.players[p_idx].transact_cash(price as i32)
+                    .expect("Receiving cash cannot fail");
+                self.emit(GameEvent::SoldHouse { player: p_idx, square: square_idx, price });
+            }
+        }
 
-                    actions::buy_house(&self, &mut owner, street_idx);
-                },
-                dialog::UserAction::SellHouse => {
-                    let player_idx = match is_in_trouble {
-                        true  => turn_idx,
-                        false => {
-                            match dialog::get_player_idx(
-                                    self, None, "Select the current owner") {
-                                Ok(s)  => s,
-                                Err(_) => {
-                                    println!("Back to the menu");
-                                    continue;
-                                }
-                            }
-                        }
-                    };
-                    
-                    let mut owner = self.players.get(player_idx).unwrap().borrow_mut();
-
-                    let eligible_streets :Vec<(usize, &square::Square)> = 
-                            self.board.iter().enumerate()
-                        .filter(|(_, s)| { match s.asset.borrow().owner() {
-                                None => false,
-                                Some(o) => o == owner.turn_idx()
-                            }
-                        })
-                        .filter(|(_, s)| s.asset.borrow().house_num() > 0 )
-                        .filter(|(_, s)| !s.asset.borrow().has_hotel())
-                        .collect();
-                    let street_idx = match dialog::get_street(eligible_streets) {
-                        Ok(s)  => s,
-                        Err(_) => {
-                            println!("Back to the menu");
-                            continue;
-                        }
-                    };
+        let cash = self.players[p_idx].cash();
+        if cash > 0 {
+            self.transfer(p_idx, cash, creditor);
+            self.emit(GameEvent::PaidDebt { player: p_idx, creditor, amount: cash });
+        }
 
-                    actions::sell_house(&self, &mut owner, street_idx);
-                },
-                dialog::UserAction::BuyHotel => {
-                    let player_idx = match dialog::get_player_idx(
-                            self, None, "Select the owner") {
-                        Ok(s)  => s,
-                        Err(_) => {
-                            println!("Back to the menu");
-                            continue;
-                        }
-                    };
-
-                    let mut owner = self.players.get(player_idx)
-                                        .unwrap().borrow_mut();
-
-                    let eligible_streets :Vec<(usize, &square::Square)> = 
-                            self.board.iter().enumerate()
-                        .filter(|(_, s)| { match s.asset.borrow().owner() {
-                                None => false,
-                                Some(u) => u == owner.turn_idx()
-                            }
-                        })
-                        .filter(|(_, s)| s.asset.borrow().house_num() == 4)
-                        .collect();
-                    let street_idx = match dialog::get_street(eligible_streets) {
-                        Ok(s)  => s,
-                        Err(_) => {
-                            println!("Back to the menu");
-                            continue;
-                        }
-                    };
+        for square_idx in 0..self.board.len() {
+            if self.board[square_idx].asset().owner() != Some(p_idx) {
+                continue;
+            }
+            let c_idx = match creditor {
+                player::Creditor::Player(c) => c,
+                player::Creditor::Bank => {
+                    self.board[square_idx].asset_mut().liquify();
+                    self.mortgage_choices.retain(|&s| s != square_idx);
+                    self.emit(GameEvent::ReturnedToBank { player: p_idx, square: square_idx });
+                    self.auctions.push(square_idx);
+                    continue;
+                }
+            };
+            self.board[square_idx].asset_mut().set_owner(c_idx);
+            self.emit(GameEvent::TransferredProperty {
+                from: p_idx, to: c_idx, square: square_idx });
+            self.take_over_mortgage(c_idx, square_idx);
+        }
 
-                    actions::buy_hotel(&self, &mut owner, street_idx);
+        // the bank puts the cards back at the bottom of their decks
+        for (deck, card) in self.players[p_idx].give_up_jail_free_cards() {
+            match creditor {
+                player::Creditor::Player(c_idx) => {
+                    self.players[c_idx].receive_jail_free_card(deck, card);
+                    self.emit(GameEvent::ReceivedJailCard { player: c_idx });
                 },
-                dialog::UserAction::SellHotel => {
-                    let player_idx = match is_in_trouble {
-                        true  => turn_idx,
-                        false => {
-                            match dialog::get_player_idx(
-                                    self, None, "Select the current owner") {
-                                Ok(s)  => s,
-                                Err(_) => {
-                                    println!("Back to the menu");
-                                    continue;
-                                }
-                            }
-                        }
-                    };
-                    
-                    let mut owner = self.players.get(player_idx).unwrap().borrow_mut();
-
-                    let eligible_streets :Vec<(usize, &square::Square)> = 
-                            self.board.iter().enumerate()
-                        .filter(|(_, s)| { match s.asset.borrow().owner() {
-                                None => false,
-                                Some(u) => u == owner.turn_idx()
-                            }
-                        })
-                        .filter(|(_, s)| s.asset.borrow().has_hotel())
-                        .collect();
-                    let street_idx = match dialog::get_street(eligible_streets) {
-                        Ok(s)  => s,
-                        Err(_) => {
-                            println!("Back to the menu");
-                            continue;
-                        }
-                    };
+                player::Creditor::Bank => self.deck_mut(deck).push(card)
+            };
+        }
+    }
 
-                    actions::sell_hotel(&self, &mut owner, street_idx);
+    /// Player pays a bill to the creditor
+    // A player who can't pay the full amount pays what they have, and owes the rest
+    fn pay(&mut self, p_idx: usize, amount: u32, creditor: player::Creditor,
+           reason: player::DebtReason) {
+        let paid = std::cmp::min(amount, self.players[p_idx].cash());
+        self.transfer(p_idx, paid, creditor);
+        if self.feeds_jackpot(creditor, reason) {
+            self.jackpot += paid;
+        }
+        if paid > 0 || amount == 0 {
+            self.emit(match reason {
+                player::DebtReason::Rent { square } => GameEvent::PaidRent {
+                    player: p_idx, owner: match creditor {
+                        player::Creditor::Player(c) => c,
+                        player::Creditor::Bank => unreachable!("Rent is paid to a player")
+                    }, square, amount: paid },
+                player::DebtReason::Tax { square } =>
+                    GameEvent::PaidTax { player: p_idx, square, amount: paid },
+                player::DebtReason::Card => match creditor {
+                    player::Creditor::Bank => GameEvent::PaidBank { player: p_idx, amount: paid },
+                    player::Creditor::Player(c) =>
+                        GameEvent::PaidPlayer { player: p_idx, to: c, amount: paid }
                 },
-                dialog::UserAction::Mortgage => {
-                    let player_idx = match is_in_trouble {
-                        true  => turn_idx,
-                        false => {
-                            match dialog::get_player_idx(
-                                    self, None, "Select the current owner") {
-                                Ok(s)  => s,
-                                Err(_) => {
-                                    println!("Back to the menu");
-                                    continue;
-                                }
-                            }
-                        }
-                    };
-                    
-                    let mut owner = self.players.get(player_idx).unwrap().borrow_mut();
-
-                    let eligible_streets :Vec<(usize, &square::Square)> = 
-                            self.board.iter().enumerate()
-                        .filter(|(_, s)| { match s.asset.borrow().owner() {
-                                None => false,
-                                Some(u) => u == owner.turn_idx()
-                            }
-                        })
-                        .filter(|(_, s)| !s.asset.borrow().is_mortgaged() )
-                        .collect();
-                    let street_idx = match dialog::get_street(eligible_streets) {
-                        Ok(s)  => s,
-                        Err(_) => {
-                            println!("Back to the menu");
-                            continue;
-                        }
-                    };
+                player::DebtReason::Bail => GameEvent::PaidBank { player: p_idx, amount: paid },
+                player::DebtReason::Interest { square } =>
+                    GameEvent::PaidInterest { player: p_idx, square, amount: paid }
+            });
+        }
+        if paid < amount {
+            let debt = player::Debt { amount: amount - paid, creditor, reason };
+            self.players[p_idx].owe(debt);
+            self.emit(GameEvent::BecameInTrouble { player: p_idx, debt });
+        }
+    }
 
-                    actions::mortgage_street(&self, &mut owner, street_idx);
-                },
-                dialog::UserAction::Unmortgage => {
-                    let player_idx = match dialog::get_player_idx(
-                            self, None, "Select the current owner") {
-                        Ok(s)  => s,
-                        Err(_) => {
-                            println!("Back to the menu");
-                            continue;
-                        }
-                    };
-
-                    let mut owner = self.players.get(player_idx)
-                                        .unwrap().borrow_mut();
-
-                    let eligible_streets :Vec<(usize, &square::Square)> = self.board.iter().enumerate()
-                        .filter(|(_, s)| { match s.asset.borrow().owner() {
-                                None => false,
-                                Some(u) => u == owner.turn_idx()
-                            }
-                        })
-                        .filter(|(_, s)| s.asset.borrow().is_mortgaged() )
-                        .collect();
-                    let street_idx = match dialog::get_street(eligible_streets) {
-                        Ok(s)  => s,
-                        Err(_) => {
-                            println!("Back to the menu");
-                            continue;
-                        }
-                    };
+    /// Check if a payment goes to the Free Parking jackpot, instead of the bank
+    fn feeds_jackpot(&self, creditor: player::Creditor, reason: player::DebtReason) -> bool {
+        let fine = matches!(reason, player::DebtReason::Tax { .. } | player::DebtReason::Card);
+        fine && creditor == player::Creditor::Bank && self.rules().free_parking_jackpot
+    }
 
+    /// Move cash from a player to the creditor
+    fn transfer(&mut self, p_idx: usize, amount: u32, creditor: player::Creditor) {
+        self.players[p_idx].transact_cash(-(amount as i32)).expect("Cash was checked");
+        if let player::Creditor::Player(c_idx) = creditor {
+            self.players[c_idx].transact_cash(amount as i32)
+                .expect("Receiving cash cannot fail");
+        }
+    }
 
-                    actions::unmortgage_street(&self, &mut owner, street_idx);
+    /// Players in trouble pay what they can towards their debts
+    fn collect_debts(&mut self) {
+        for p_idx in 0..self.players.len() {
+            if !self.players[p_idx].is_in_trouble() {
+                continue;
+            }
+            while let Some(debt) = self.players[p_idx].debts().first().cloned() {
+                let amount = std::cmp::min(debt.amount, self.players[p_idx].cash());
+                if amount == 0 {
+                    break;
                 }
+                self.transfer(p_idx, amount, debt.creditor);
+                if self.feeds_jackpot(debt.creditor, debt.reason) {
+                    self.jackpot += amount;
+                }
+                self.players[p_idx].reduce_debt(amount);
+                self.emit(GameEvent::PaidDebt {
+                    player: p_idx, creditor: debt.creditor, amount });
+            }
+            if !self.players[p_idx].is_in_trouble() {
+                self.emit(GameEvent::OutOfTrouble { player: p_idx });
             }
         }
     }
 
-    /// Capture player name, and price, and complete purchase
-    fn auction(&self, square: &square::Square) 
-            -> Result<(), ()> {
-        println!("Auction!!");
-        let owner_idx = match dialog::get_player_idx(self, None,
-                                                     "Select the new owner") {
-            Ok(o)  => o,
-            Err(_) => {
-                println!("Back to the menu");
-                return Ok(());
+    /// Auction the streets the bank took over from a bankrupt player
+    // The turn goes back to its current phase once they are all auctioned
+    fn start_queued_auctions(&mut self) {
+        if let Some(&square_idx) = self.auctions.first() {
+            self.resume_phase = self.phase();
+            self.start_auction(square_idx);
+        }
+    }
+
+    /// Continue the game, once a street is sold at auction or nobody buys it
+    // Streets of a bankrupt player are auctioned one after the other, before the
+    // turn goes on
+    fn finish_auction(&mut self, square_idx: usize) {
+        self.bidding = None;
+        let queued = self.auctions.first() == Some(&square_idx);
+        self.auctions.retain(|&s| s != square_idx);
+        if !queued {
+            self.set_phase(TurnPhase::Actions);
+            return;
+        }
+        match self.auctions.first() {
+            Some(&next) => self.start_auction(next),
+            None => self.set_phase(self.resume_phase)
+        };
+    }
+
+    /// Find the owner of a street
+    fn street_owner(&self, street_idx: usize) -> Result<usize, GameError> {
+        let square = match self.board.get(street_idx) {
+            Some(s) => s,
+            None    => return Err(GameError::NoSuchStreet)
+        };
+        match square.asset().owner() {
+            Some(o) => Ok(o),
+            None    => Err(GameError::Unowned)
+        }
+    }
+
+    /// Execute an action on a street, on behalf of the owner of the street
+    fn street_action(&mut self, street_idx: usize,
+                     action: fn(&mut Game, usize, usize) -> Result<(), GameError>)
+            -> Result<(), GameError> {
+        let owner_idx = self.street_owner(street_idx)?;
+        action(self, owner_idx, street_idx)
+    }
+
+    /// Owner of a street sells it to another player
+    fn sell_street(&mut self, street_idx: usize, buyer_idx: usize, price: u32)
+            -> Result<(), GameError> {
+        let owner_idx = self.street_owner(street_idx)?;
+        let buyer = match self.players.get(buyer_idx) {
+            Some(p) => p,
+            None    => return Err(GameError::NoSuchPlayer)
+        };
+        if owner_idx == buyer_idx {
+            return Err(GameError::SameOwner);
+        }
+        if buyer.left_game() {
+            return Err(GameError::PlayerLeftGame { player: buyer_idx });
+        }
+
+        actions::sell_street(self, owner_idx, buyer_idx, street_idx, price)
+    }
+
+    /// A player proposes a trade. It waits for an answer of the counterparty
+    fn propose_trade(&mut self, trade: trade::Trade) -> Result<(), GameError> {
+        self.validate_trade(&trade)?;
+        self.trade = Some(trade.clone());
+        self.emit(GameEvent::TradeProposed { trade });
+        Ok(())
+    }
+
+    /// The counterparty accepts the trade, and everything changes hands at once
+    // The trade is checked again, since the players may have sold or built in the
+    // meantime
+    fn accept_trade(&mut self) -> Result<(), GameError> {
+        let trade = match &self.trade {
+            Some(t) => t.clone(),
+            None    => return Err(GameError::NoTrade)
+        };
+        self.validate_trade(&trade)?;
+        self.trade = None;
+        self.emit(GameEvent::Traded { trade: trade.clone() });
+        self.hand_over(trade.proposer, trade.counterparty, &trade.gives);
+        self.hand_over(trade.counterparty, trade.proposer, &trade.receives);
+        self.pay_transfer_fees(trade.counterparty, &trade.gives);
+        self.pay_transfer_fees(trade.proposer, &trade.receives);
+        Ok(())
+    }
+
+    /// The counterparty turns the trade down
+    fn reject_trade(&mut self) -> Result<(), GameError> {
+        let trade = self.trade.take().ok_or(GameError::NoTrade)?;
+        self.emit(GameEvent::TradeRejected { player: trade.counterparty });
+        Ok(())
+    }
+
+    /// The counterparty proposes a different trade, which replaces the proposed one
+    fn counter_trade(&mut self, gives: trade::TradeOffer, receives: trade::TradeOffer)
+            -> Result<(), GameError> {
+        let counter = match &self.trade {
+            Some(t) => t.counter(gives, receives),
+            None    => return Err(GameError::NoTrade)
+        };
+        self.propose_trade(counter)
+    }
+
+    /// Check if both players can hand over everything in the trade
+    // Streets can't change hands while their suburb has buildings. The receiver of a
+    // mortgaged street pays a fee, so they need the cash for it after the trade.
+    fn validate_trade(&self, trade: &trade::Trade) -> Result<(), GameError> {
+        if trade.proposer == trade.counterparty {
+            return Err(GameError::TradeWithSelf);
+        }
+        if trade.gives.is_empty() && trade.receives.is_empty() {
+            return Err(GameError::EmptyTrade);
+        }
+        let sides = [(trade.proposer, &trade.gives, &trade.receives),
+                     (trade.counterparty, &trade.receives, &trade.gives)];
+        for &(p_idx, offer, received) in sides.iter() {
+            let player = match self.players.get(p_idx) {
+                Some(p) => p,
+                None    => return Err(GameError::NoSuchPlayer)
+            };
+            if player.left_game() {
+                return Err(GameError::PlayerLeftGame { player: p_idx });
             }
+            for (i, street_idx) in offer.streets.iter().enumerate() {
+                if offer.streets[..i].contains(street_idx) {
+                    return Err(GameError::DuplicateStreet);
+                }
+                let street = match self.board.get(*street_idx) {
+                    Some(s) => s,
+                    None    => return Err(GameError::NoSuchStreet)
+                };
+                match street.asset().owner() {
+                    Some(o) if o == p_idx => {},
+                    Some(_) => return Err(GameError::NotOwner),
+                    None    => return Err(GameError::Unowned)
+                };
+                if self.suburb_has_buildings(street) {
+                    return Err(GameError::HasBuildings);
+                }
+            }
+            if player.num_get_out_of_jail_cards() < offer.jail_cards {
+                return Err(GameError::NoJailCard);
+            }
+            let needed = offer.cash + self.transfer_fees(received);
+            let available = player.cash() + received.cash;
+            if needed > available {
+                return Err(GameError::InsufficientFunds { needed, available });
+            }
+        }
+        Ok(())
+    }
+
+    /// Hand over one side of a trade
+    fn hand_over(&mut self, from: usize, to: usize, offer: &trade::TradeOffer) {
+        for &street_idx in offer.streets.iter() {
+            self.board[street_idx].asset_mut().set_owner(to);
+        }
+        self.transfer(from, offer.cash, player::Creditor::Player(to));
+        for _ in 0..offer.jail_cards {
+            let (deck, card) = self.players[from].give_jail_free_card()
+                .expect("Cards were checked");
+            self.players[to].receive_jail_free_card(deck, card);
+        }
+    }
+
+    /// Fee for taking over a mortgaged street: 10% of the mortgage
+    fn transfer_fee(&self, street_idx: usize) -> u32 {
+        let street = &self.board[street_idx];
+        match street.asset().is_mortgaged() {
+            true  => street.get_street_details().expect("Details should exist")
+                        .mortgage() / 10,
+            false => 0
+        }
+    }
+
+    /// Fees for all mortgaged streets of one side of a trade
+    fn transfer_fees(&self, offer: &trade::TradeOffer) -> u32 {
+        offer.streets.iter()
+            .filter(|&&s| s < self.board.len())
+            .map(|&s| self.transfer_fee(s))
+            .sum()
+    }
+
+    /// Receiver of mortgaged streets pays the fees to the bank
+    fn pay_transfer_fees(&mut self, p_idx: usize, offer: &trade::TradeOffer) {
+        for &street_idx in offer.streets.iter() {
+            self.take_over_mortgage(p_idx, street_idx);
+        }
+    }
+
+    /// New owner of a mortgaged street pays 10% interest on the mortgage
+    // They can lift the mortgage straight away for the mortgage value, or keep it and
+    // pay another 10% interest when they lift it later
+    fn take_over_mortgage(&mut self, p_idx: usize, street_idx: usize) {
+        let fee = self.transfer_fee(street_idx);
+        if fee == 0 {
+            return;
+        }
+        self.pay(p_idx, fee, player::Creditor::Bank,
+                 player::DebtReason::Interest { square: street_idx });
+        if !self.mortgage_choices.contains(&street_idx) {
+            self.mortgage_choices.push(street_idx);
+        }
+    }
+
+    /// New owner of a mortgaged street keeps the mortgage for now
+    fn keep_mortgage(&mut self, street_idx: usize) -> Result<(), GameError> {
+        if !self.mortgage_choices.contains(&street_idx) {
+            return Err(GameError::NoMortgageChoice);
+        }
+        let owner_idx = self.street_owner(street_idx)?;
+        self.mortgage_choices.retain(|&s| s != street_idx);
+        self.emit(GameEvent::KeptMortgage { player: owner_idx, square: street_idx });
+        Ok(())
+    }
+
+    /// Check if any street in the suburb of the street has buildings
+    fn suburb_has_buildings(&self, street: &square::Square) -> bool {
+        let suburb = match street.get_street_details().and_then(|sd| sd.get_suburb()) {
+            Some(s) => s,
+            None    => return false
+        };
+        self.board.iter()
+            .filter(|s| s.get_street_details().and_then(|sd| sd.get_suburb()) == Some(suburb))
+            .any(|s| s.asset().has_buildings())
+    }
+
+    /// Active player buys the street they landed on
+    fn accept_purchase(&mut self) -> Result<(), GameError> {
+        let square_idx = match self.phase() {
+            TurnPhase::Purchase(s) => s,
+            _ => return Err(GameError::NothingToBuy)
         };
+        let price = self.board[square_idx].get_price();
+        self.buy_property(self.active_player(), square_idx, price)?;
+        self.auctions.retain(|&s| s != square_idx);
+        self.set_phase(TurnPhase::Actions);
+        Ok(())
+    }
 
-        let purchase_price = match dialog::get_purchase_price(square) {
-            Ok(p) => p,
-            Err(_) => {
-                println!("Back to the menu");
-                return Ok(());
+    /// Active player doesn't buy the street, which then goes on auction
+    fn decline_purchase(&mut self) -> Result<(), GameError> {
+        match self.phase() {
+            TurnPhase::Purchase(square_idx) => self.start_auction(square_idx),
+            TurnPhase::Auction(square_idx) => self.finish_auction(square_idx),
+            TurnPhase::HouseAuction => self.finish_house_auction(),
+            _ => return Err(GameError::NothingToBuy)
+        };
+        Ok(())
+    }
+
+    /// Put a street on auction
+    // Every player still in the game can bid, starting with the active player
+    fn start_auction(&mut self, square_idx: usize) {
+        let player_num = self.players.len();
+        let bidders = (0..player_num)
+            .map(|i| (self.active_player() + i) % player_num)
+            .filter(|&p| !self.players[p].left_game())
+            .collect::<Vec<usize>>();
+        self.bidding = Some(auction::Auction::new(auction::Lot::Street(square_idx), bidders,
+                                                  auction::OPENING_BID));
+        self.set_phase(TurnPhase::Auction(square_idx));
+        self.emit(GameEvent::AuctionStarted { square: square_idx });
+    }
+
+    /// Put one of the last houses on auction, when there aren't enough for everyone
+    // Only the players who can build bid, starting with the active player. Bidding
+    // starts at the lowest building price of their streets.
+    fn auction_house(&mut self) -> Result<(), GameError> {
+        match self.phase() {
+            TurnPhase::Actions  => {},
+            TurnPhase::Roll     => return Err(GameError::DiceNotRolled),
+            TurnPhase::GameOver => return Err(GameError::GameOver),
+            _ => return Err(GameError::PurchasePending)
+        };
+        if self.house_bid.is_some() {
+            return Err(GameError::HouseBidPending);
+        }
+        if !self.house_shortage() {
+            return Err(GameError::NoHouseShortage);
+        }
+        let sites = self.house_sites();
+        let player_num = self.players.len();
+        let bidders = (0..player_num)
+            .map(|i| (self.active_player() + i) % player_num)
+            .filter(|&p| sites.iter().any(|&(owner, _)| owner == p))
+            .collect::<Vec<usize>>();
+        let opening_bid = sites.iter().map(|&(_, price)| price).min()
+            .expect("A shortage has builders");
+        self.bidding = Some(auction::Auction::new(auction::Lot::House, bidders, opening_bid));
+        self.set_phase(TurnPhase::HouseAuction);
+        self.emit(GameEvent::HouseAuctionStarted { opening_bid });
+        Ok(())
+    }
+
+    /// Continue the turn once the house is sold, or nobody bought it
+    fn finish_house_auction(&mut self) {
+        self.bidding = None;
+        self.set_phase(TurnPhase::Actions);
+    }
+
+    /// The bidder whose turn it is raises the highest bid, up to the cash they have
+    fn place_bid(&mut self, amount: u32) -> Result<(), GameError> {
+        let mut bidding = self.bidding.clone().ok_or(GameError::NoAuction)?;
+        let player = bidding.bidder().ok_or(GameError::NoAuction)?;
+        let available = self.players[player].cash();
+        if amount > available {
+            return Err(GameError::InsufficientFunds { needed: amount, available });
+        }
+        let bid = bidding.bid(amount)?;
+        self.continue_auction(bidding, GameEvent::BidPlaced { player, amount: bid.amount })
+    }
+
+    /// The bidder whose turn it is drops out of the auction
+    fn pass_bid(&mut self) -> Result<(), GameError> {
+        let mut bidding = self.bidding.clone().ok_or(GameError::NoAuction)?;
+        let player = bidding.pass()?;
+        self.continue_auction(bidding, GameEvent::PassedBid { player })
+    }
+
+    /// Record a bid or a pass, and sell the street to the winner once the auction is over
+    // The winner may have spent cash since their bid, so that is checked before
+    // anything changes
+    fn continue_auction(&mut self, bidding: auction::Auction, event: GameEvent)
+            -> Result<(), GameError> {
+        let winner = bidding.winner();
+        if let Some(bid) = winner {
+            let available = self.players[bid.player].cash();
+            if available < bid.amount {
+                return Err(GameError::InsufficientFunds { needed: bid.amount, available });
+            }
+        }
+        self.emit(event);
+        let lot = bidding.lot();
+        let is_over = bidding.is_over();
+        self.bidding = Some(bidding);
+        if !is_over {
+            return Ok(());
+        }
+        match lot {
+            auction::Lot::Street(square_idx) => {
+                match winner {
+                    Some(bid) => self.buy_property(bid.player, square_idx, bid.amount)?,
+                    None      => self.emit(GameEvent::NoBids { square: square_idx })
+                };
+                self.finish_auction(square_idx);
+            },
+            auction::Lot::House => {
+                // the winner builds the house once they choose a street, see buy_house
+                if let Some(bid) = winner {
+                    self.house_bid = Some(bid);
+                    self.emit(GameEvent::WonHouse { player: bid.player, price: bid.amount });
+                }
+                self.finish_house_auction();
             }
         };
+        Ok(())
+    }
+
+    /// Capture the bid of the player whose turn it is in the auction
+    fn capture_auction(&mut self) -> GameCommand {
+        let bidding = match &self.bidding {
+            Some(b) => b.clone(),
+            None    => return GameCommand::DeclinePurchase
+        };
+        let bidder = bidding.bidder().expect("Auction should have bidders");
+        let highest = match bidding.highest_bid() {
+            Some(b) => format!("{} bids ${}", self.players[b.player].name(), b.amount),
+            None    => String::from("Nobody has bid yet")
+        };
+        let lot = match bidding.lot() {
+            auction::Lot::Street(s) => self.board[s].name().to_string(),
+            auction::Lot::House     => String::from("the house")
+        };
+        let message = format!("{} for {}. {}, raise the bid to at least ${}?", highest, lot,
+                              self.players[bidder].name(), bidding.minimum_bid());
+        if !self.yes_no(&message) {
+            return GameCommand::PassBid;
+        }
+        let amount = match bidding.lot() {
+            auction::Lot::Street(s) => self.decisions.purchase_price(&self.board[s]),
+            auction::Lot::House     => self.decisions.amount()
+        };
+        match amount {
+            Some(amount) => GameCommand::Bid { amount },
+            None         => GameCommand::PassBid
+        }
+    }
+
+    /// Capture the owner of the streets for a menu action
+    // A player in trouble can only sell their own assets
+    fn capture_owner(&mut self, is_in_trouble: bool, msg: &str) -> Option<usize> {
+        match is_in_trouble {
+            true  => Some(self.debtor().unwrap_or(self.active_player())),
+            false => self.select_player(None, msg)
+        }
+    }
+
+    /// Capture one of the streets of the owner, that matches the filter
+    fn capture_street<F>(&mut self, owner_idx: usize, filter: F) -> Option<usize>
+            where F: Fn(&player::Asset) -> bool {
+        let eligible_streets :Vec<(usize, &square::Square)> =
+                self.board.iter().enumerate()
+            .filter(|(_, s)| { match s.asset().owner() {
+                    None => false,
+                    Some(u) => u == owner_idx
+                }
+            })
+            .filter(|(_, s)| filter(s.asset()))
+            .collect();
+        self.decisions.street(eligible_streets)
+    }
+
+    /// Capture everything a player hands over in a trade
+    fn capture_trade_offer(&mut self, p_idx: usize) -> Option<trade::TradeOffer> {
+        let name = self.players[p_idx].name().to_string();
+        let mut offer = trade::TradeOffer::default();
+        while self.yes_no(&format!("Does {} hand over a street?", name)) {
+            offer.streets.push(self.capture_street(p_idx, |a| !a.has_buildings())?);
+        }
+        println!("Cash {} hands over", name);
+        offer.cash = self.decisions.amount()?;
+        if self.players[p_idx].num_get_out_of_jail_cards() > 0 {
+            println!("Get-out-of-jail-free cards {} hands over", name);
+            offer.jail_cards = self.decisions.amount()?;
+        }
+        Some(offer)
+    }
+
+    /// Capture if the new owner of a mortgaged street lifts the mortgage now
+    fn capture_mortgage_choice(&mut self, street_idx: usize) -> GameCommand {
+        let street = &self.board[street_idx];
+        let details = street.get_street_details().expect("Details should exist");
+        let owner_idx = street.asset().owner().expect("Street should have an owner");
+        let message = format!("{}, lift the mortgage of {} now for ${}? Later it costs ${}",
+                              self.players[owner_idx].name(), street.name(),
+                              details.mortgage(), details.get_unmortgage_amount());
+        match self.yes_no(&message) {
+            true  => GameCommand::Unmortgage { street: street_idx },
+            false => GameCommand::KeepMortgage { street: street_idx }
+        }
+    }
+
+    /// Capture how the active player pays the income tax
+    fn capture_tax_choice(&mut self) -> GameCommand {
+        let choice = self.tax_choice.expect("Tax choice should exist");
+        let message = format!("Pay ${} of your net worth instead of ${} income tax?",
+                              choice.share, choice.amount);
+        GameCommand::PayIncomeTax { net_worth: self.yes_no(&message) }
+    }
+
+    /// Capture the answer of the counterparty to the proposed trade
+    fn capture_trade_response(&mut self, trade: trade::Trade) -> GameCommand {
+        let name = self.players[trade.counterparty].name().to_string();
+        if self.yes_no(&format!("{}, do you accept the trade?", name)) {
+            return GameCommand::AcceptTrade;
+        }
+        if !self.yes_no("Make a counter offer?") {
+            return GameCommand::RejectTrade;
+        }
+        let gives = self.capture_trade_offer(trade.counterparty);
+        let receives = self.capture_trade_offer(trade.proposer);
+        match (gives, receives) {
+            (Some(gives), Some(receives)) => GameCommand::CounterTrade { gives, receives },
+            _ => GameCommand::RejectTrade
+        }
+    }
 
-        let mut owner = self.players.get(owner_idx).expect("Player should exist")
-                            .borrow_mut();
-        self.buy_property(&mut *owner, square, purchase_price)
+    /// Confirm the purchase of a building with the user
+    fn confirm_building(&mut self, building: &str, street_idx: usize) -> bool {
+        let street = &self.board[street_idx];
+        let message = match street.get_street_details().and_then(|sd| sd.get_suburb()) {
+            None => return true, // the game rejects buildings here
+            Some(s) => format!("Confirm: Buy a {} on {} for ${}?",
+                               building, street.name(), s.building_price())
+        };
+        self.yes_no(&message)
+    }
+
+    /// Capture the user's menu selection, as a command for the game
+    fn capture_user_action(&mut self) -> Option<GameCommand> {
+        // a player in trouble outside their turn settles their debts first
+        let debtor = self.debtor();
+        let is_in_trouble = debtor.is_some()
+            || self.players[self.active_player()].is_in_trouble();
+        if let Some(p_idx) = debtor {
+            println!("{} has to pay their debts, or leave the game", self.players[p_idx].name());
+        }
+        let option = self.decisions.user_action(is_in_trouble);
+        let command = match option {
+            dialog::UserAction::EndGame => match debtor {
+                Some(player) => GameCommand::GoBankrupt { player },
+                None         => GameCommand::LeaveGame
+            },
+            dialog::UserAction::FinishGame => {
+                if !self.yes_no("Do all players agree to end the game?") {
+                    return None;
+                }
+                GameCommand::EndGame
+            },
+            dialog::UserAction::EndTurn => GameCommand::EndTurn,
+            dialog::UserAction::Undo => GameCommand::Undo,
+            dialog::UserAction::UndoTurn => GameCommand::UndoTurn,
+            dialog::UserAction::Redo => GameCommand::Redo,
+            dialog::UserAction::SellStreet => {
+                let owner_idx = self.capture_owner(is_in_trouble,
+                                                   "Select the current owner")?;
+                let street = self.capture_street(owner_idx, |_| true)?;
+                let buyer = self.select_player(Some(owner_idx), "Select the new owner")?;
+                let price = self.decisions.amount()?;
+                GameCommand::SellStreet { street, buyer, price }
+            },
+            dialog::UserAction::Trade => {
+                let proposer = self.capture_owner(is_in_trouble,
+                                                  "Select the player proposing the trade")?;
+                let counterparty = self.select_player(Some(proposer),
+                                                      "Select the player to trade with")?;
+                let gives = self.capture_trade_offer(proposer)?;
+                let receives = self.capture_trade_offer(counterparty)?;
+                GameCommand::ProposeTrade {
+                    trade: trade::Trade { proposer, counterparty, gives, receives }
+                }
+            },
+            dialog::UserAction::BuyHouse if self.house_bid.is_some() => {
+                let bid = self.house_bid.expect("Winning bid should exist");
+                println!("{} builds the house won at auction", self.players[bid.player].name());
+                let street = self.capture_street(bid.player, |a| a.house_num() < 4)?;
+                GameCommand::BuyHouse { street }
+            },
+            dialog::UserAction::BuyHouse if self.house_shortage() => {
+                println!("There aren't enough houses for everyone. The highest bid wins");
+                GameCommand::AuctionHouse
+            },
+            dialog::UserAction::BuyHouse => {
+                let owner_idx = self.select_player(None, "Select the owner")?;
+                let street = self.capture_street(owner_idx, |a| a.house_num() < 4)?;
+                if !self.confirm_building("house", street) {
+                    return None;
+                }
+                GameCommand::BuyHouse { street }
+            },
+            dialog::UserAction::SellHouse => {
+                let owner_idx = self.capture_owner(is_in_trouble,
+                                                   "Select the current owner")?;
+                let street = self.capture_street(owner_idx,
+                                                 |a| a.house_num() > 0 && !a.has_hotel())?;
+                GameCommand::SellHouse { street }
+            },
+            dialog::UserAction::BuyHotel => {
+                let owner_idx = self.select_player(None, "Select the owner")?;
+                let street = self.capture_street(owner_idx, |a| a.house_num() == 4)?;
+                if !self.confirm_building("hotel", street) {
+                    return None;
+                }
+                GameCommand::BuyHotel { street }
+            },
+            dialog::UserAction::SellHotel => {
+                let owner_idx = self.capture_owner(is_in_trouble,
+                                                   "Select the current owner")?;
+                let street = self.capture_street(owner_idx, |a| a.has_hotel())?;
+                GameCommand::SellHotel { street }
+            },
+            dialog::UserAction::Mortgage => {
+                let owner_idx = self.capture_owner(is_in_trouble,
+                                                   "Select the current owner")?;
+                let street = self.capture_street(owner_idx, |a| !a.is_mortgaged())?;
+                GameCommand::Mortgage { street }
+            },
+            dialog::UserAction::Unmortgage => {
+                let owner_idx = self.select_player(None, "Select the current owner")?;
+                let street = self.capture_street(owner_idx, |a| a.is_mortgaged())?;
+                GameCommand::Unmortgage { street }
+            }
+        };
+        Some(command)
     }
 
     /// Execute action on card
-    fn execute_card(&self, card: &card::Card)
-            -> Result <(), ()> {
-        println!("{}", card.description());
+    // The dice are the roll that brought the player to the card
+    fn execute_card(&mut self, card: &card::Card, dice: &Dice) {
+        let p_idx = self.active_player();
         match card.action() {
             card::CardAction::Movement =>  {
                 // calculate the dice number based on square
                 let target = card.square().expect("Target square should exist");
-                let p_pos = {
-                    self.players.get(self.active_player())
-                                     .unwrap().borrow_mut().position() as u32
-                };
+                let p_pos = self.players[p_idx].position() as u32;
                 let dice = match target > p_pos {
                     true  => Dice::new(target - p_pos, 0),
-                    false => Dice::new(target + BOARD_SIZE - p_pos, 0)
+                    false => Dice::new(target + self.board.len() as u32 - p_pos, 0)
                 };
-                return self.execute_turn(dice);
+                self.execute_turn(dice);
             },
             card::CardAction::RelativeMovement => {
-                let movement = card.square().expect("Target square should exist");
-                return self.execute_turn(Dice::new(movement, 0));
+                let steps = card.amount().expect("Amount should exist");
+                match steps < 0 {
+                    true  => self.move_player_back(steps.unsigned_abs(), dice.clone()),
+                    false => self.execute_turn(Dice::new(steps as u32, 0))
+                };
+            },
+            card::CardAction::MoveToNearest(square_type) => {
+                let p_pos = self.players[p_idx].position();
+                let board_size = self.board.len();
+                let steps = match (1..=board_size)
+                    .find(|s| self.board[(p_pos + s) % board_size].square_type()
+                              == *square_type) {
+                    Some(s) => s as u32,
+                    None    => return // the board has no square of this type
+                };
+                let factor = card.amount().expect("Amount should exist") as u32;
+                let rent = match square_type {
+                    square::SquareType::Utility => Rent::DiceTimes(factor),
+                    _                           => Rent::Multiplied(factor)
+                };
+                self.move_player(steps, dice.clone(), rent);
+            },
+            card::CardAction::Payment => {
+                let amount = card.amount().expect("Amount should exist");
+                if amount < 0 {
+                    let amount = amount.unsigned_abs();
+                    self.players[p_idx].transact_cash(amount as i32)
+                        .expect("Receiving cash cannot fail");
+                    self.emit(GameEvent::CollectedFromBank { player: p_idx, amount });
+                } else {
+                    self.pay(p_idx, amount as u32, player::Creditor::Bank,
+                             player::DebtReason::Card);
+                }
+            },
+            card::CardAction::CollectFromPlayers => {
+                let amount = card.amount().expect("Amount should exist") as u32;
+                for other in self.other_players(p_idx) {
+                    self.pay(other, amount, player::Creditor::Player(p_idx),
+                             player::DebtReason::Card);
+                }
             },
-            card::CardAction::Payment => {
-                let mut player = self.players.get(self.active_player())
-                                     .unwrap().borrow_mut();
-                return player.transact_cash(-1 * card.amount()
-                        .expect("Amount should exist"));
+            card::CardAction::PayPlayers => {
+                let amount = card.amount().expect("Amount should exist") as u32;
+                for other in self.other_players(p_idx) {
+                    self.pay(p_idx, amount, player::Creditor::Player(other),
+                             player::DebtReason::Card);
+                }
             },
             card::CardAction::Jail => {
-                let mut player = self.players.get(self.active_player())
-                                     .unwrap().borrow_mut();
-                player.go_to_jail();
-            },
-            card::CardAction::JailRelease => {
-                let mut player = self.players.get(self.active_player())
-                                     .unwrap().borrow_mut();
-                player.receive_jail_free_card();
+                self.send_to_jail(p_idx);
             },
+            card::CardAction::JailRelease => {}, // the player keeps the card, see draw_card
+
             card::CardAction::Repairs => {
-                let mut player = self.players.get(self.active_player())
-                                     .unwrap().borrow_mut();
                 let assets = self.board.iter()
-                    .filter(|s| match s.asset.borrow().owner() {
+                    .map(|s| s.asset())
+                    .filter(|a| match a.owner() {
                         None => false,
-                        Some(o) => o == player.turn_idx()
+                        Some(o) => o == p_idx
                     })
-                    .map(|s| &s.asset)
-                    .collect::<Vec<&RefCell<player::Asset>>>();
+                    .collect::<Vec<&player::Asset>>();
                 let house_num = assets.iter()
-                    .filter(|a| !a.borrow().has_hotel())
-                    .fold(0, |sum, a| sum + a.borrow().house_num());
+                    .filter(|a| !a.has_hotel())
+                    .fold(0, |sum, a| sum + a.house_num());
                 let hotel_num = assets.iter()
-                    .filter(|a| a.borrow().has_hotel())
+                    .filter(|a| a.has_hotel())
                     .fold(0, |sum, _| sum + 1);
                 let total = (house_num * card.amount().unwrap() as u32)
                           + (hotel_num * card.square().unwrap());
-                println!("You need to pay a total of ${}", total);
-                return player.transact_cash(-1 * total as i32);
+                self.pay(p_idx, total, player::Creditor::Bank, player::DebtReason::Card);
             }
         }
-        Ok(())
     }
 
     /// Get all squares owned by a player
     fn get_player_owned_squares(&self, player_idx: usize) -> Vec<&square::Square> {
         let mut squares = Vec::<&square::Square>::new();
         for s in self.board.iter() {
-            match s.asset.borrow().owner() {
+            match s.asset().owner() {
                 Some(owner_idx) => {
                     if owner_idx == player_idx {
                         squares.push(&s);
@@ -934,255 +1980,277 @@ impl Game {
     }
 
     /// Actions on corner squares
-    fn execute_square_corner(&self)
-            -> Result<(), ()> {
-        let mut player = self.players.get(self.active_player()).unwrap().borrow_mut();
-        let square = self.board.get(player.position()).unwrap();
-        if player.position() == 30 {
-            player.go_to_jail();
-        } else {
-            println!("{}", square.name());
-        }
-        Ok(())
+    /// Send the player straight to jail
+    fn send_to_jail(&mut self, p_idx: usize) {
+        let jail = self.board.iter()
+            .position(|s| s.square_type() == square::SquareType::Jail)
+            .expect("Board should have a jail");
+        self.players[p_idx].go_to_jail(jail);
+        self.emit(GameEvent::WentToJail { player: p_idx });
     }
 
-    fn execute_square_tax(&self) -> Result<(), ()> {
-        let mut player = self.players.get(self.active_player()).unwrap().borrow_mut();
-        match player.position() {
-            4 => {
-                println!("Oh No! Pay $200 in Income Tax!");
-                player.transact_cash(-200)
-            },
-            38 => {
-                println!("Oh No! Pay $100 in Luxury Tax!");
-                player.transact_cash(-100)
-            }
-            _ => {println!("Error, undefined Tax"); Ok(()) }
+    /// Landed on Free Parking, collecting the jackpot
+    fn execute_square_free_parking(&mut self) {
+        if self.jackpot == 0 {
+            return;
         }
+        let p_idx = self.active_player();
+        let amount = std::mem::take(&mut self.jackpot);
+        self.players[p_idx].transact_cash(amount as i32).expect("Receiving cash cannot fail");
+        self.emit(GameEvent::WonJackpot { player: p_idx, amount });
     }
 
-    fn execute_square_community(&self) -> Result<(), ()> {
-        println!("COMMUNITY CHEST!");
-        let mut cards = self.community_cards.borrow_mut();
-        let card = cards.remove(0);
-        match self.execute_card(&card) {
-            Ok(_) => {
-                // All good
-                cards.push(card);
-                Ok(())
+    fn execute_square_tax(&mut self, amount: u32, percent: Option<u32>) {
+        let p_idx = self.active_player();
+        let square = self.players[p_idx].position();
+        match percent {
+            Some(percent) if self.rules().income_tax_choice => {
+                // the player pays once they chose how, a share of what they own now
+                let share = self.worth(p_idx, false) * percent / 100;
+                self.tax_choice = Some(TaxChoice { square, amount, share });
             },
-            Err(_) => {
-                // player couldn't pay. return card to its original position
-                cards.insert(0, card);
-                Err(())
-            }
+            _ => self.pay(p_idx, amount, player::Creditor::Bank,
+                          player::DebtReason::Tax { square })
+        };
+    }
+
+    /// Active player pays the income tax they landed on, as they chose
+    fn pay_income_tax(&mut self, net_worth: bool) -> Result<(), GameError> {
+        let p_idx = self.active_player();
+        let choice = self.tax_choice.take().ok_or(GameError::NoTaxChoice)?;
+        let amount = match net_worth {
+            true  => choice.share,
+            false => choice.amount
+        };
+        self.pay(p_idx, amount, player::Creditor::Bank,
+                 player::DebtReason::Tax { square: choice.square });
+        Ok(())
+    }
+
+    fn execute_square_community(&mut self, dice: &Dice) {
+        self.draw_card(card::Deck::CommunityChest, dice);
+    }
+
+    fn execute_square_chance(&mut self, dice: &Dice) {
+        self.draw_card(card::Deck::Chance, dice);
+    }
+
+    /// Players still in the game, other than the player
+    fn other_players(&self, p_idx: usize) -> Vec<usize> {
+        self.players.iter()
+            .filter(|p| p.turn_idx() != p_idx && !p.left_game())
+            .map(|p| p.turn_idx())
+            .collect()
+    }
+
+    /// Draw the top card of the deck, and execute it
+    // The card goes to the bottom of the deck, except for a get-out-of-jail-free card.
+    // The player keeps that one out of the deck, until it's used.
+    fn draw_card(&mut self, deck: card::Deck, dice: &Dice) {
+        let p_idx = self.active_player();
+        let square = self.players[p_idx].position();
+        if self.deck_mut(deck).is_empty() {
+            // every card is a get-out-of-jail-free card, held by the players
+            self.emit(GameEvent::DeckEmpty { player: p_idx, square });
+            return;
+        }
+        let card = self.deck_mut(deck).remove(0);
+        self.emit(GameEvent::DrewCard {
+            player: p_idx,
+            square,
+            description: card.description().to_string() });
+        if *card.action() == card::CardAction::JailRelease {
+            self.players[p_idx].receive_jail_free_card(deck, card);
+            self.emit(GameEvent::ReceivedJailCard { player: p_idx });
+            return;
         }
+        self.execute_card(&card, dice);
+        self.deck_mut(deck).push(card);
     }
 
-    fn execute_square_chance(&self) -> Result<(), ()> {
-        println!("CHANCE!");
-        let mut cards = self.chance_cards.borrow_mut();
-        let card = cards.remove(0);
-        match self.execute_card(&card) {
-            Ok(_) => {
-                // All good
-                cards.push(card);
-                Ok(())
-            },
-            Err(_) => {
-                // player couldn't pay. return card to its original position
-                cards.insert(0, card);
-                Err(())
-            }
+    fn deck_mut(&mut self, deck: card::Deck) -> &mut Vec<card::Card> {
+        match deck {
+            card::Deck::Chance         => &mut self.chance_cards,
+            card::Deck::CommunityChest => &mut self.community_cards
         }
     }
 
     /// Sell property to another player
-    fn sell_property(&self, orig_owner: &mut player::Player, new_owner: &mut player::Player,
-                     square: &square::Square, price: u32) -> Result<(), ()> {
-
-        if new_owner.cash() < price {
-            println!("{} has insufficient funds", &new_owner.name());
-            return Err(());
+    fn sell_property(&mut self, orig_owner: usize, new_owner: usize,
+                     square_idx: usize, price: u32) -> Result<(), GameError> {
+        let available = self.players[new_owner].cash();
+        let needed = price + self.transfer_fee(square_idx);
+        if available < needed {
+            return Err(GameError::InsufficientFunds { needed, available });
         }
 
         // ensure street has no houses
-        if square.asset.borrow().house_num() > 0 {
-            let msg = "Confirm: Sell the street with building on it?";
-            if self.is_unit_test { // assume 'No' for unit tests
-                return Err(());
-            }
-            match super::dialog::yes_no(&msg) {
-                true => {}
-                false => return Err(()),
-            }
+        if self.board[square_idx].asset().has_buildings() {
+            return Err(GameError::HasBuildings);
         }
 
         // new_owner has enough cash
-        println!("{} sells {} to {} for ${}",
-                 orig_owner.name(), square.name(), new_owner.name(), price);
-        orig_owner.transact_cash(price as i32);
-        new_owner.transact_cash(-1 * (price as i32));
-        let mut asset = square.asset.borrow_mut();
-        asset.set_owner(new_owner.turn_idx());
+        self.players[orig_owner].transact_cash(price as i32)
+            .expect("Receiving cash cannot fail");
+        self.players[new_owner].transact_cash(-(price as i32)).expect("Cash was checked");
+        self.board[square_idx].asset_mut().set_owner(new_owner);
+        self.emit(GameEvent::SoldStreet {
+            seller: orig_owner, buyer: new_owner, square: square_idx, price });
+        self.take_over_mortgage(new_owner, square_idx);
         Ok(())
     }
 
     /// Purchase the property
-    fn buy_property(&self, new_owner: &mut player::Player,
-                    square: &square::Square, price: u32) -> Result<(), ()> {
+    fn buy_property(&mut self, new_owner: usize,
+                    square_idx: usize, price: u32) -> Result<(), GameError> {
         // buying from scratch
-        println!("You buy {} for ${}", square.name(), price);
-        if new_owner.transact_cash(-1 * (price as i32)).is_err() {
-            return Err(()); // should never happen, since price check was done already
-        };
-        let mut asset = square.asset.borrow_mut();
-        asset.set_owner(new_owner.turn_idx());
+        self.players[new_owner].transact_cash(-(price as i32))?;
+        self.board[square_idx].asset_mut().set_owner(new_owner);
+        self.emit(GameEvent::BoughtProperty { player: new_owner, square: square_idx, price });
         Ok(())
     }
 
     /// Landed on a square that can be bought
-    fn execute_square_property(&self, dice: Dice) 
-            -> Result<(), ()> {
-        let square = self.get_player_square(); 
-        println!("You landed on {}", square.name());
-        let has_owner = match square.asset.borrow().owner() {
-            Some(_) => true,
-            None => false
-        };
-        match has_owner {
-            false => { // Unowned asset
-                // For unit tests, purchase automatically, with no auction option
-                if self.is_unit_test {
-                    let mut player = self.players.get(self.active_player())
-                            .unwrap().borrow_mut();
-                    return self.buy_property(&mut player, square, square.get_price());
-                }
-
-                let player_cash = self.players.get(self.active_player())
-                    .unwrap().borrow().cash();
-                if player_cash < square.get_price() {
-                    println!("You can't afford to buy this street.");
-                    return self.auction(square);
-                }
-                let message = format!("Do you want to buy {} for ${}?",
-                                      square.name(), square.get_price());
-                match super::dialog::yes_no(&message) {
-                    true => {
-                        let mut player = self.players.get(self.active_player())
-                                .unwrap().borrow_mut();
-                        self.buy_property(&mut player, square, square.get_price())
-                    },
-                    false => self.auction(square)
+    // An unowned street is offered to the active player. If they can't afford it,
+    // it goes on auction.
+    fn execute_square_property(&mut self, dice: Dice, rent: Rent) {
+        let p_idx = self.active_player();
+        let square_idx = self.players[p_idx].position();
+        let square = &self.board[square_idx];
+        let owner_idx = match square.asset().owner() {
+            None => { // Unowned asset
+                let price = square.get_price();
+                if self.players[p_idx].cash() < price {
+                    self.emit(GameEvent::CannotAfford { player: p_idx, square: square_idx });
+                    self.start_auction(square_idx);
+                } else {
+                    self.emit(GameEvent::PurchaseOffered {
+                        player: p_idx, square: square_idx, price });
+                    self.set_phase(TurnPhase::Purchase(square_idx));
                 }
+                return;
             },
-            true => { // Owned asset
-                let asset = square.asset.borrow();
-                let owner_idx = asset.owner().unwrap();
-                let mut player = self.players.get(self.active_player())
-                    .unwrap().borrow_mut();
-                if owner_idx == player.turn_idx() {
-                    println!("Phew! Luckily it's yours");
-                    return Ok(());
-                }
+            Some(o) => o
+        };
 
-                if asset.is_mortgaged() {
-                    println!("Phew! {} is mortgaged, so no rent is due", square.name());
-                    return Ok(());
-                }
-                let rent = self.calculate_rent(square, dice).expect("Rent should exist");
+        // Owned asset
+        if owner_idx == p_idx {
+            self.emit(GameEvent::NoRent { player: p_idx, square: square_idx, mortgaged: false });
+            return;
+        }
 
-                let mut owner = self.players.get(owner_idx)
-                    .expect("Owner should exist").borrow_mut();
-                println!("Oh no! You pay ${} to {}", rent, owner.name());
-                if player.transact_cash(-1 * (rent as i32)).is_err() {
-                    return Err(()); // player is now in trouble
-                };
-                owner.transact_cash(rent as i32);
-                Ok(())
-            }
+        if square.asset().is_mortgaged() {
+            self.emit(GameEvent::NoRent { player: p_idx, square: square_idx, mortgaged: true });
+            return;
         }
+        if self.rules().no_rent_in_jail && self.players[owner_idx].is_in_jail() {
+            self.emit(GameEvent::OwnerInJail {
+                player: p_idx, owner: owner_idx, square: square_idx });
+            return;
+        }
+        if let Rent::DiceTimes(factor) = rent {
+            // the player rolls the dice again, see roll_dice
+            self.rent_roll = Some(factor);
+            self.emit(GameEvent::RollForRent {
+                player: p_idx, owner: owner_idx, square: square_idx, factor });
+            return;
+        }
+        self.pay_rent(dice, rent);
+    }
+
+    /// Active player pays the rent of the square they are on
+    // The player is in trouble if they can't pay
+    fn pay_rent(&mut self, dice: Dice, rent: Rent) {
+        let p_idx = self.active_player();
+        let square_idx = self.players[p_idx].position();
+        let square = &self.board[square_idx];
+        let owner_idx = square.asset().owner().expect("Square should have an owner");
+        let rent = self.calculate_rent(square, dice, rent).expect("Rent should exist");
+        self.pay(p_idx, rent, player::Creditor::Player(owner_idx),
+                 player::DebtReason::Rent { square: square_idx });
     }
 
     /// Execute the turn of a player
     // The turn starts with a player moving. Then, once the player is on the new square,
-    // the rules for that new square execute. Lastly, other players may want to execute 
-    // transactions
-    fn execute_turn(&self, mut dice: Dice) 
-            -> Result<(), ()> {
-        // rolling double has special rules
-        while dice.is_double() {
-
-            if dice.is_zeros() {
-                break; // player stays on same square, to get out of trouble
-            }
+    // the rules for that new square execute. The dice passed in are final: doubles
+    // were already rolled again by the player.
+    fn execute_turn(&mut self, dice: Dice) {
+        let p_idx = self.active_player();
 
-            let mut player = self.players.get(self.active_player()).unwrap().borrow_mut();
-            if player.is_in_jail(){
-                println!("YAY, you're released from jail");
-                player.leave_jail();
-                break;
-            }
+        // player doesn't advance if in jail and didn't roll double
+        if self.players[p_idx].is_in_jail() && !self.jail_time(&dice) {
+            return;
+        }
 
-            if dice.num_rolls == 3 {
-                player.go_to_jail();
-                return Ok(());
-            }
+        // rolling a double the third time sends the player to jail
+        if dice.is_double() && dice.num_rolls == 3 {
+            self.send_to_jail(p_idx);
+            return;
+        }
 
-            // rolled a double
-            println!("A double. Roll again");
-            let d = match self.is_unit_test {
-                true => Dice::new(10, 20), // random roll
-                false => dialog::capture_dice_roll()
+        self.move_player(dice.cumulative_sum(), dice, Rent::Standard);
+    }
+
+    /// Move the active player forward, and execute the rules of the new square
+    fn move_player(&mut self, steps: u32, dice: Dice, rent: Rent) {
+        let p_idx = self.active_player();
+        let player = &mut self.players[p_idx];
+        let old_pos = player.position();
+        player.advance(steps, self.board.len() as u32);
+        let new_pos = player.position();
+        self.emit(GameEvent::Moved { player: p_idx, from: old_pos, to: new_pos });
+
+        if new_pos < old_pos {
+            let rules = self.rules();
+            let amount = match new_pos == 0 && rules.double_go_salary {
+                true  => rules.go_salary * 2, // landed exactly on GO
+                false => rules.go_salary
             };
-            dice.reroll(d);
+            self.players[p_idx].transact_cash(amount as i32)
+                .expect("Receiving cash cannot fail");
+            self.emit(GameEvent::PassedGo { player: p_idx, amount });
         }
+        self.execute_square(dice, rent);
+    }
 
-        // player doesn't advance if in jail and didn't roll double
-        if self.players.get(self.active_player()).unwrap().borrow().is_in_jail() {
-            return Ok(());
-        }
+    /// Move the active player back, and execute the rules of the new square
+    // Going back past GO doesn't pay the salary
+    fn move_player_back(&mut self, steps: u32, dice: Dice) {
+        let p_idx = self.active_player();
+        let player = &mut self.players[p_idx];
+        let old_pos = player.position();
+        player.go_back(steps, self.board.len() as u32);
+        let new_pos = player.position();
+        self.emit(GameEvent::Moved { player: p_idx, from: old_pos, to: new_pos });
+        self.execute_square(dice, Rent::Standard);
+    }
 
-        {
-            let mut player = self.players.get(self.active_player()).unwrap().borrow_mut();
-      
-            let old_pos = player.position();
-            player.advance(dice.cumulative_sum(), BOARD_SIZE);
-
-            if player.position() < old_pos {
-                println!("Yay! You pass begin and collect $200");
-                player.transact_cash(200);
-            }
-        }
-        
-        let r = match self.get_player_square().square_type() {
+    /// Execute the rules of the square the active player landed on
+    fn execute_square(&mut self, dice: Dice, rent: Rent) {
+        let p_idx = self.active_player();
+        match self.get_player_square().square_type() {
             square::SquareType::Utility |
             square::SquareType::Station |
-            square::SquareType::Street        => self.execute_square_property(dice),
-            square::SquareType::Corner        => self.execute_square_corner(),
-            square::SquareType::Tax           => self.execute_square_tax(),
-            square::SquareType::CommunityCard => self.execute_square_community(),
-            square::SquareType::ChanceCard    => self.execute_square_chance()
-        };
-
-        let mut player = self.players.get(self.active_player()).unwrap().borrow_mut();
-        // Player is in trouble if the transaction failed
-        match r {
-            Ok(_)  => player.set_in_trouble(false),
-            Err(_) => player.set_in_trouble(true)
+            square::SquareType::Street        => self.execute_square_property(dice, rent),
+            square::SquareType::Go |
+            square::SquareType::Jail          => {},
+            square::SquareType::FreeParking   => self.execute_square_free_parking(),
+            square::SquareType::GoToJail      => self.send_to_jail(p_idx),
+            square::SquareType::Tax { amount, percent } =>
+                self.execute_square_tax(amount, percent),
+            square::SquareType::CommunityCard => self.execute_square_community(&dice),
+            square::SquareType::ChanceCard    => self.execute_square_chance(&dice)
         };
-        Ok(())
     }
 
     /// Get type of square the current player is on
     fn get_player_square(&self) -> &square::Square {
-        let player = self.players.get(self.active_player()).unwrap().borrow();
-        &self.board[player.position()]
+        &self.board[self.players[self.active_player()].position()]
     }
 
     /// Calculate if a player owns all streets in the suburb
     fn player_owns_suburb(&self, player_idx: usize, street: &square::Square) -> bool {
-        match street.asset.borrow().owner() {
+        match street.asset().owner() {
             None    => return false, // nobody own this street
             Some(o) => {
                 if o != player_idx {
@@ -1197,7 +2265,7 @@ impl Game {
                     Some(sd) => sd.get_suburb() == suburb,
                     None => false
                 })
-            .filter(|s| match s.asset.borrow().owner() {
+            .filter(|s| match s.asset().owner() {
                     Some(o) => o != player_idx, // others own this
                     None    => true // nobody owns this
                 })
@@ -1212,8 +2280,7 @@ impl Game {
     fn street_eligible_for_house(&self, street: &square::Square) -> bool {
         let street_details = street.get_street_details().unwrap();
         let suburb = street_details.get_suburb();
-        let asset = street.asset.borrow();
-        let building_num = asset.house_num();
+        let building_num = street.asset().house_num();
 
         // current building num cannot be > 1 of lowest number of buildings on street
         // ie, if we're building house no 2, all other streets must have at least 1 house
@@ -1224,8 +2291,8 @@ impl Game {
                     None => false
                 })
             // if any street has less houses, cannot buy house
-            .fold(true, |acc, s| { 
-                acc && building_num <= s.asset.borrow().house_num()
+            .fold(true, |acc, s| {
+                acc && building_num <= s.asset().house_num()
             })
     }
 
@@ -1233,8 +2300,7 @@ impl Game {
     fn street_eligible_for_house_sale(&self, street: &square::Square) -> bool {
         let street_details = street.get_street_details().unwrap();
         let suburb = street_details.get_suburb();
-        let asset = street.asset.borrow();
-        let building_num = asset.house_num();
+        let building_num = street.asset().house_num();
 
         // current building num cannot be > 1 of lowest number of buildings on street
         // ie, if we're building house no 2, all other streets must have at least 1 house
@@ -1245,8 +2311,8 @@ impl Game {
                     None => false
                 })
             // if any street has more houses, cannot sell house
-            .fold(true, |acc, s| { 
-                acc && building_num >= s.asset.borrow().house_num()
+            .fold(true, |acc, s| {
+                acc && building_num >= s.asset().house_num()
             })
     }
 
@@ -1262,273 +2328,208 @@ impl Game {
                     Some(sd) => sd.get_suburb() == suburb,
                     None => false
                 })
-            .fold(true, |acc, s| { 
-                let a = s.asset.borrow();
+            .fold(true, |acc, s| {
+                let a = s.asset();
                 acc && (a.has_hotel() || a.house_num() == 4 )
             })
     }
 }
 
 /// Shuffle the deck of chance or community chest cards
-fn shuffle_cards(cards: &mut Vec<card::Card>) {
+fn shuffle_cards(cards: &mut Vec<card::Card>, rng: &mut StdRng) {
     let mut idxs: Vec<usize> = (0..cards.len()).collect();
 
-    idxs.shuffle(&mut thread_rng());
+    idxs.shuffle(rng);
     for i in idxs.iter() {
         let c = cards.remove(0);
         cards.insert(*i, c);
     }
 }
 
-/// Load the chance cards
-fn load_chance_cards() -> Vec<card::Card> {
-    let mut cards = Vec::new();
-
-    cards.push(card::Card::new("GO TO JAIL!", card::CardAction::Jail, None, None));
-    cards.push(card::Card::new("Advance to St. Charles Place",
-                         card::CardAction::Movement, None, Some(11)));
-    cards.push(card::Card::new("Make general repairs on all your property. House, $25 each; Hotel, $100 each", card::CardAction::Repairs, Some(25), Some(100)));
-    cards.push(card::Card::new("Go forward three spaces",
-                         card::CardAction::RelativeMovement, None, Some(3)));
-    cards.push(card::Card::new("You have been elected chairman of the board. Pay $50",
-                         card::CardAction::Payment, Some(50), None));
-    cards.push(card::Card::new("Take a trip to Reading Railroad.",
-                         card::CardAction::Movement, None, Some(5)));
-    cards.push(card::Card::new("Speeding fine. Pay $15",
-                         card::CardAction::Payment, Some(15), None));
-    cards.push(card::Card::new("Your building load matures. Receive $150",
-                         card::CardAction::Payment, Some(-150), None));
-    cards.push(card::Card::new("Advance to Boardwalk",
-                         card::CardAction::Movement, None, Some(39)));
-    cards.push(card::Card::new("Go back three spaces",
-                         card::CardAction::RelativeMovement, None, Some(37)));
-    cards.push(card::Card::new("Advance to Illinois Avenue",
-                         card::CardAction::Movement, None, Some(24)));
-    cards.push(card::Card::new("Advance to GO. Collect $200",
-                         card::CardAction::Movement, None, Some(0)));
-    cards.push(card::Card::new("GET OUT OF JAIL FREE.",
-                         card::CardAction::JailRelease, None, None));
-    cards.push(card::Card::new("You win the lottery. Receive $500", card::CardAction::Payment, Some(-150), None));
-    cards.push(card::Card::new("Advance to Water works.", card::CardAction::Movement, None, Some(28)));
-    shuffle_cards(&mut cards);
-    cards
+/// Initialize the game
+// Initializes the game by setting up the necessary data structures.
+pub fn init(player_names: Vec::<String>, mode: GameMode, rules: rules::Rules) -> Game {
+    init_with_seed(player_names, mode, rules, thread_rng().gen())
 }
 
-/// Load the community chest cards
-fn load_community_chest_cards() -> Vec<card::Card> {
-    let mut cards = Vec::new();
-    cards.push(card::Card::new("You are assessed for Street repairs: $40 per House, $115 per Hotel", card::CardAction::Repairs, Some(40), Some(115)));
-    cards.push(card::Card::new("GET OUT OF JAIL FREE.", card::CardAction::JailRelease, None, None));
-    cards.push(card::Card::new("You have won second prize in a beauty contest. Collect $10",
-                         card::CardAction::Payment, Some(-10), None));
-    cards.push(card::Card::new("Life insurance matures. Collect $100",
-                         card::CardAction::Payment, Some(-100), None));
-    cards.push(card::Card::new("It's your birthday. Collect $40",
-                         card::CardAction::Payment, Some(-40), None));
-//                         card::CardAction::PaymentPlayers, Some(-40), None));
-    cards.push(card::Card::new("Advance to GO. Collect $200",
-                         card::CardAction::Movement, None, Some(0)));
-    cards.push(card::Card::new("You inherit $100",
-                         card::CardAction::Payment, Some(-100), None));
-    cards.push(card::Card::new("Bank error in your favor. Collect $200",
-                         card::CardAction::Payment, Some(-200), None));
-    cards.push(card::Card::new("From sale of stock, you get $50",
-                         card::CardAction::Payment, Some(-50), None));
-    cards.push(card::Card::new("Collect $25 consultancy fee",
-                         card::CardAction::Payment, Some(-25), None));
-    cards.push(card::Card::new("Holiday fund matures. Collect $100",
-                         card::CardAction::Payment, Some(-100), None));
-    cards.push(card::Card::new("Doctor's fees. Pay $50", card::CardAction::Payment, Some(50), None));
-    cards.push(card::Card::new("Hospital fees. Pay $100",
-                         card::CardAction::Payment, Some(100), None));
-    cards.push(card::Card::new("GO TO JAIL!", card::CardAction::Jail, None, None));
-    cards.push(card::Card::new("School fees. Pay $50", card::CardAction::Payment, Some(50), None));
-    cards.push(card::Card::new("Income tax refund. Collect $20",
-                         card::CardAction::Payment, Some(-20), None));
-
-    shuffle_cards(&mut cards);
-    cards
+/// Initialize the game, with the cards shuffled according to the seed
+// The same seed and players always result in the same game
+pub fn init_with_seed(player_names: Vec::<String>, mode: GameMode, rules: rules::Rules,
+                      seed: u64) -> Game {
+    init_with_edition(player_names, mode, rules, seed, edition::Edition::us(),
+                      card::Decks::classic())
+        .expect("Classic decks should fit the US board")
 }
 
-/// Initialize the game
-// Initializes the game by setting up the necessary data structures.
-pub fn init(player_names: Vec::<String>) -> Game {
-    let mut players = Vec::<RefCell<player::Player>>::new();
+/// Initialize the game with another board, or other chance and community chest cards
+// Fails if the cards move players past the end of the board
+pub fn init_with_edition(player_names: Vec::<String>, mode: GameMode, rules: rules::Rules,
+                         seed: u64, edition: edition::Edition, decks: card::Decks)
+                         -> Result<Game, String> {
+    decks.fit_board(edition.squares.len())?;
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut chance_cards = decks.chance.clone();
+    shuffle_cards(&mut chance_cards, &mut rng);
+    let mut community_cards = decks.community_chest.clone();
+    shuffle_cards(&mut community_cards, &mut rng);
+    let board = edition.squares();
+    let journal = journal::Journal::new(seed, mode, rules, player_names.clone(), edition, decks);
+    let mut players = Vec::<player::Player>::new();
     // Create player objects
     for (i, p) in player_names.iter().enumerate() {
-        players.push(RefCell::new(player::Player::new(p.to_string(), i)));
+        players.push(player::Player::new(p.to_string(), i, rules.starting_cash));
     }
 
-    Game {
+    Ok(Game {
         players,
-        active_player: RefCell::new(0),
-        chance_cards: RefCell::new(load_chance_cards()),
-        community_cards: RefCell::new(load_community_chest_cards()),
-        board: load_squares(),
-        is_unit_test: false
-    }
+        active_player: 0,
+        chance_cards,
+        community_cards,
+        board,
+        decisions: Box::new(decision::TerminalDecisions),
+        phase: TurnPhase::Roll,
+        dice: None,
+        auctions: Vec::new(),
+        resume_phase: TurnPhase::Roll,
+        bidding: None,
+        house_bid: None,
+        trade: None,
+        mortgage_choices: Vec::new(),
+        rent_roll: None,
+        tax_choice: None,
+        jackpot: 0,
+        turn: 1,
+        deadline: match mode {
+            GameMode::TimeLimit(minutes) => Some(now() + minutes * 60),
+            _ => None
+        },
+        events: Vec::new(),
+        journal
+    })
 }
 
-/// Load the squares of the board into memory
-fn load_squares() -> [square::Square; BOARD_SIZE as usize] {
-    [
-        square::Square::new("Just chillin' at the start", square::SquareType::Corner, None),
-        square::Square::new("Mediterranean Avenue", square::SquareType::Street, 
-            Some(square::StreetDetails::new(
-                    'B', 60, 2, [4, 10, 30, 90, 160, 250], 30))),
-        square::Square::new("Community Chest", square::SquareType::CommunityCard, None),
-        square::Square::new("Baltic Avenue", square::SquareType::Street, 
-            Some(square::StreetDetails::new(
-                    'B', 60, 4, [8, 20, 60, 180, 320, 450], 30))),
-        square::Square::new("Income Tax", square::SquareType::Tax, None),
-        square::Square::new("Reading Railroad", square::SquareType::Station,
-            Some(square::StreetDetails::new('N', 200, 0, [0,0,0,0,0,0], 100))),
-        square::Square::new("Oriental Avenue", square::SquareType::Street, 
-            Some(square::StreetDetails::new(
-                    'L', 100, 6, [12, 30, 90, 270, 400, 550], 50))),
-        square::Square::new("Chance", square::SquareType::ChanceCard, None),
-        square::Square::new("Vermont Avenue", square::SquareType::Street, 
-            Some(square::StreetDetails::new(
-                    'L', 100, 6, [12, 30, 90, 270, 440, 550], 50))),
-        square::Square::new("Connecticut Avenue", square::SquareType::Street, 
-            Some(square::StreetDetails::new(
-                    'L', 120, 8, [16, 40, 100, 300, 450, 600], 60))),
-        square::Square::new("Visiting Jail", square::SquareType::Corner, None),
-        square::Square::new("St. Charles Place", square::SquareType::Street, 
-            Some(square::StreetDetails::new(
-                    'P', 140, 10, [20, 50, 150, 450, 625, 750], 70))),
-        square::Square::new("Electric Company", square::SquareType::Utility,
-            Some(square::StreetDetails::new('N', 150, 0, [0,0,0,0,0,0], 75))),
-        square::Square::new("States Avenue", square::SquareType::Street, 
-            Some(square::StreetDetails::new(
-                    'P', 140, 10, [20, 50,150, 450, 625, 750], 70))),
-        square::Square::new("Virginia Avenue", square::SquareType::Street, 
-            Some(square::StreetDetails::new(
-                    'P', 160, 12, [24, 60, 180, 500, 700, 900], 80))),
-        square::Square::new("Pennsylvania Railroad", square::SquareType::Station,
-            Some(square::StreetDetails::new('N', 200, 0, [0,0,0,0,0,0], 100))),
-        square::Square::new("St. James Place", square::SquareType::Street, 
-            Some(square::StreetDetails::new(
-                    'O', 180, 14, [28, 70, 200, 550, 750, 950], 90))),
-        square::Square::new("Community Chest", square::SquareType::CommunityCard, None),
-        square::Square::new("Tennessee Avenue", square::SquareType::Street, 
-            Some(square::StreetDetails::new(
-                    'O', 180, 14, [28, 70, 200, 550, 750, 950], 90))),
-        square::Square::new("New York Avenue", square::SquareType::Street, 
-            Some(square::StreetDetails::new(
-                    'O', 200, 16, [32, 80, 220, 600, 800, 1000], 110))),
-        square::Square::new("Yay! Free Parking", square::SquareType::Corner, None),
-        square::Square::new("Kentucky Avenue", square::SquareType::Street, 
-            Some(square::StreetDetails::new(
-                    'R', 220, 18, [36, 90, 250, 700, 875, 1050], 110))),
-        square::Square::new("Chance", square::SquareType::ChanceCard, None),
-        square::Square::new("Indiana Avenue", square::SquareType::Street, 
-            Some(square::StreetDetails::new(
-                    'R', 220, 18, [36, 90, 250, 700, 875, 1050], 110))),
-        square::Square::new("Illinois Avenue", square::SquareType::Street, 
-            Some(square::StreetDetails::new(
-                    'R', 240, 20, [40, 100, 300, 750, 925, 1100], 120))),
-        square::Square::new("B. & O. Railroad", square::SquareType::Station,
-            Some(square::StreetDetails::new('N', 200, 0, [0,0,0,0,0,0], 100))),
-        square::Square::new("Atlantic Avenue", square::SquareType::Street, 
-            Some(square::StreetDetails::new(
-                    'Y', 260, 22, [44, 110, 330, 800, 975, 1150], 130))),
-        square::Square::new("Ventnor Avenue", square::SquareType::Street, 
-            Some(square::StreetDetails::new(
-                    'Y', 260, 22, [44, 110, 330, 800, 975, 1150], 130))),
-        square::Square::new("Water Works", square::SquareType::Utility,
-            Some(square::StreetDetails::new('N', 150, 0, [0,0,0,0,0,0], 75))),
-        square::Square::new("Marvin Gardens", square::SquareType::Street, 
-            Some(square::StreetDetails::new(
-                    'Y', 280, 24, [48, 120, 360, 850, 1025, 1200], 140))),
-        square::Square::new("Go To Jail", square::SquareType::Corner, None),
-        square::Square::new("Pacific Avenue", square::SquareType::Street, 
-            Some(square::StreetDetails::new(
-                    'G', 300, 26, [52, 130, 390, 900, 1100, 1275], 150))),
-        square::Square::new("North Carolina Avenue", square::SquareType::Street, 
-            Some(square::StreetDetails::new(
-                    'G', 300, 26, [52, 130, 390, 900, 1100, 1275], 150))),
-        square::Square::new("Community Chest", square::SquareType::CommunityCard, None),
-        square::Square::new("Pennsylvania Avenue", square::SquareType::Street, 
-            Some(square::StreetDetails::new(
-                    'G', 320, 28, [56, 150, 450, 1000, 1200, 1400], 160))),
-        square::Square::new("Short Line", square::SquareType::Station,
-            Some(square::StreetDetails::new('N', 200, 0, [0,0,0,0,0,0], 100))),
-        square::Square::new("Chance", square::SquareType::ChanceCard, None),
-        square::Square::new("Park Place", square::SquareType::Street, 
-            Some(square::StreetDetails::new(
-                    'I', 350, 35, [70, 175, 500, 1100, 1300, 1500], 175))),
-        square::Square::new("Luxury Tax", square::SquareType::Tax, None),
-        square::Square::new("Boardwalk", square::SquareType::Street, 
-            Some(square::StreetDetails::new(
-                    'I', 400, 50, [100, 200, 600, 1400, 1700, 2000], 200)))
-    ]
+/// Current time, in seconds since the Unix epoch
+fn now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    const SEED: u64 = 1;
+
+    /// New game, with the cards in the same order on every run
+    fn game(player_names: Vec<String>, mode: GameMode, rules: rules::Rules) -> Game {
+        init_with_seed(player_names, mode, rules, SEED)
+    }
+
+    fn jail_card() -> card::Card {
+        card::Card::new("GET OUT OF JAIL FREE.", card::CardAction::JailRelease, None, None)
+    }
+
+    /// Move the active player, and buy the street they land on
+    fn move_and_buy(g: &mut Game, dice: Dice) {
+        g.execute_turn(dice);
+        if let TurnPhase::Purchase(_) = g.phase() {
+            g.apply(GameCommand::BuyProperty);
+        }
+    }
+
     #[test]
     fn initialize_game() {
         let v = vec!["Bob".to_string(),"Joe".to_string(),"Sally".to_string()];
         let len = v.len();
-        let g = init(v);
+        let g = game(v, GameMode::Classic, rules::Rules::official());
         assert_eq!(g.players.len(), len, "All players created");
-        assert_eq!(g.players.get(0).unwrap().borrow().name(), "Bob", "First player");
-        assert_eq!(g.players.get(1).unwrap().borrow().name(), "Joe", "Middle player");
-        assert_eq!(g.players.get(2).unwrap().borrow().name(), "Sally", "Last player");
+        assert_eq!(g.players[0].name(), "Bob", "First player");
+        assert_eq!(g.players[1].name(), "Joe", "Middle player");
+        assert_eq!(g.players[2].name(), "Sally", "Last player");
     }
 
     #[test]
     fn pay_income_tax() {
-        let g = init(vec!["Test".to_string()]);
-        g.execute_turn(Dice::new(4, 0)); // income tax, $200
-        let p = g.players.get(0).unwrap().borrow();
+        let mut g = game(vec!["Test".to_string()], GameMode::Classic, rules::Rules::official());
+        move_and_buy(&mut g, Dice::new(4, 0)); // income tax, $200
+        let p = &g.players[0];
         assert_eq!(p.cash(), 1300);
     }
 
+    #[test]
+    fn income_tax_choice() {
+        let mut g = game(vec!["A".to_string(), "B".to_string()], GameMode::Classic,
+                         rules::Rules::classic());
+        g.apply(GameCommand::RollDice(1, 3)); // Income Tax, $200 or 10%
+        assert_eq!(g.tax_choice(), Some(TaxChoice { square: 4, amount: 200, share: 150 }));
+        assert_eq!(g.players[0].cash(), 1500);
+        assert_eq!(g.apply(GameCommand::EndTurn),
+                   vec![GameEvent::Rejected { error: GameError::TaxChoicePending }]);
+        assert_eq!(g.apply(GameCommand::PayIncomeTax { net_worth: true }),
+                   vec![GameEvent::PaidTax { player: 0, square: 4, amount: 150 }]);
+        assert_eq!(g.tax_choice(), None);
+        assert_eq!(g.apply(GameCommand::PayIncomeTax { net_worth: true }),
+                   vec![GameEvent::Rejected { error: GameError::NoTaxChoice }]);
+        g.apply(GameCommand::EndTurn);
+
+        // streets count for their printed price, even when mortgaged, and buildings
+        // for what they cost
+        g.board[1].asset_mut().set_owner(1);
+        g.board[1].asset_mut().buy_house().unwrap();
+        g.board[3].asset_mut().set_owner(1);
+        g.board[3].asset_mut().mortgage();
+        assert_eq!(g.net_worth(1), 1500 + 60 + 50 + 30);
+        g.apply(GameCommand::RollDice(1, 3));
+        assert_eq!(g.tax_choice().map(|c| c.share), Some((1500 + 60 + 50 + 60) / 10));
+        g.apply(GameCommand::PayIncomeTax { net_worth: false });
+        assert_eq!(g.players[1].cash(), 1300);
+
+        // the luxury tax has no choice
+        g.apply(GameCommand::EndTurn);
+        g.execute_turn(Dice::new(34, 0));
+        assert_eq!(g.tax_choice(), None);
+        assert_eq!(g.players[0].cash(), 1250);
+    }
+
     #[test]
     fn test_pass_go() {
-        let g = init(vec!["Test".to_string()]);
+        let mut g = game(vec!["Test".to_string()], GameMode::Classic, rules::Rules::official());
 
         // advance on top of GO
-        g.execute_turn(Dice::new(10, 0)); // visiting jail
-        g.execute_turn(Dice::new(30, 0)); // on the go square
+        move_and_buy(&mut g, Dice::new(10, 0)); // visiting jail
+        move_and_buy(&mut g, Dice::new(30, 0)); // on the go square
         {
-            let p = g.players.get(0).unwrap().borrow();
+            let p = &g.players[0];
             assert_eq!(p.cash(), 1700);
         }
 
         // advance past GO
-        g.execute_turn(Dice::new(20, 0)); // free parking
+        move_and_buy(&mut g, Dice::new(20, 0)); // free parking
         {
-            let p = g.players.get(0).unwrap().borrow();
+            let p = &g.players[0];
             assert_eq!(p.cash(), 1700);
         }
-        g.execute_turn(Dice::new(30, 0)); // pass go, to visiting jail
+        move_and_buy(&mut g, Dice::new(30, 0)); // pass go, to visiting jail
         {
-            let p = g.players.get(0).unwrap().borrow();
+            let p = &g.players[0];
             assert_eq!(p.cash(), 1900);
         }
     }
 
     #[test]
     fn jail_time() {
-        let mut g = init(vec!["Test".to_string()]);
-        g.set_unit_test(); // skip dialog and pay $50 to be released from jail
+        let mut g = game(vec!["Test".to_string()], GameMode::Classic, rules::Rules::official());
         g.set_active_player(0);
 
         // go to jail
         {
-            let mut p = g.players.get(0).unwrap().borrow_mut();
-            p.receive_jail_free_card();
+            let p = &mut g.players[0];
+            p.receive_jail_free_card(card::Deck::Chance, jail_card());
             assert_eq!(p.is_in_jail(), false);
         }
 
         {
-            g.execute_turn(Dice::new(30, 0));
-            let p = g.players.get(0).unwrap().borrow();
+            move_and_buy(&mut g, Dice::new(30, 0));
+            let p = &g.players[0];
             assert_eq!(p.position(), 10);
             assert_eq!(p.is_in_jail(), true);
             assert_eq!(p.cash(), 1500);
@@ -1537,8 +2538,8 @@ mod tests {
 
         // now release, using card
         {
-            g.jail_time();
-            let p = g.players.get(0).unwrap().borrow();
+            g.apply(GameCommand::UseJailCard);
+            let p = &g.players[0];
             assert_eq!(p.num_get_out_of_jail_cards(), 0);
             assert_eq!(p.is_in_jail(), false);
             assert_eq!(p.cash(), 1500);
@@ -1546,50 +2547,134 @@ mod tests {
 
         {
             // back in jail
-            g.execute_turn(Dice::new(20, 0));
-            let p = g.players.get(0).unwrap().borrow();
+            move_and_buy(&mut g, Dice::new(20, 0));
+            let p = &g.players[0];
             assert_eq!(p.is_in_jail(), true);
             assert_eq!(p.num_get_out_of_jail_cards(), 0);
             assert_eq!(p.cash(), 1500);
         }
 
         // now release, paying $50
-        g.jail_time();
-        let p = g.players.get(0).unwrap().borrow();
+        g.apply(GameCommand::PayBail);
+        let p = &g.players[0];
         assert_eq!(p.is_in_jail(), false);
         assert_eq!(p.num_get_out_of_jail_cards(), 0);
         assert_eq!(p.cash(), 1450);
     }
 
+    #[test]
+    fn pay_bail_after_three_turns_in_jail() {
+        let mut g = game(vec!["Jailbird".to_string()], GameMode::Classic, rules::Rules::official());
+        g.players[0].go_to_jail(10);
+        assert_eq!(g.apply(GameCommand::UseJailCard),
+                   vec![GameEvent::Rejected { error: GameError::NoJailCard }]);
+
+        for turns in 1..3 {
+            let events = g.apply(GameCommand::RollDice(1, 2));
+            assert_eq!(events, vec![GameEvent::ServedJailTurn { player: 0, turns }]);
+            assert_eq!(g.players[0].jail_turns(), turns);
+            g.apply(GameCommand::EndTurn);
+        }
+
+        // the third roll without a double costs the bail, and the player moves anyway
+        let events = g.apply(GameCommand::RollDice(1, 2));
+        assert_eq!(events[..3], [
+            GameEvent::LeftJail { player: 0, release: JailRelease::TimeServed { bail: 50 } },
+            GameEvent::PaidBank { player: 0, amount: 50 },
+            GameEvent::Moved { player: 0, from: 10, to: 13 }
+        ]);
+        assert_eq!(g.players[0].is_in_jail(), false);
+        assert_eq!(g.players[0].jail_turns(), 0);
+        assert_eq!(g.players[0].cash(), 1450);
+    }
+
+    #[test]
+    fn house_rules() {
+        let mut g = game(vec!["A".to_string(), "B".to_string()], GameMode::Classic,
+                         rules::Rules::family());
+        g.board[1].asset_mut().set_owner(1);
+        g.players[1].go_to_jail(10);
+        g.execute_turn(Dice::new(1, 0));
+        assert!(g.events.contains(&GameEvent::OwnerInJail { player: 0, owner: 1, square: 1 }));
+        assert_eq!(g.players[0].cash(), 1500);
+
+        // landing exactly on GO pays double
+        g.execute_card(&card::Card::new("test", card::CardAction::Movement, None, Some(0)),
+                       &Dice::new(0, 0));
+        assert_eq!(g.players[0].cash(), 1900);
+
+        let mut g = game(vec!["A".to_string()], GameMode::Classic, rules::Rules::quick());
+        assert_eq!(g.players[0].cash(), 2500);
+        g.players[0].go_to_jail(10);
+        assert_eq!(g.apply(GameCommand::PayBail), vec![GameEvent::LeftJail {
+            player: 0, release: JailRelease::Bail { amount: 100 } }]);
+        assert_eq!(g.players[0].cash(), 2400);
+        g.execute_turn(Dice::new(30, 0)); // landing exactly on GO only pays the salary
+        assert!(g.events.contains(&GameEvent::PassedGo { player: 0, amount: 400 }));
+    }
+
+    #[test]
+    fn free_parking_jackpot() {
+        let mut g = game(vec!["A".to_string(), "B".to_string()], GameMode::Classic,
+                         rules::Rules::family());
+        g.players[0].transact_cash(-1400).unwrap();
+        g.apply(GameCommand::RollDice(1, 3)); // Income Tax, $200
+        assert_eq!(g.jackpot(), 100);
+        g.players[0].transact_cash(150).unwrap();
+        g.collect_debts(); // pays the rest of the tax
+        assert_eq!(g.jackpot(), 200);
+        g.apply(GameCommand::EndTurn);
+
+        g.execute_turn(Dice::new(20, 0)); // Free Parking
+        assert_eq!(g.events.last(), Some(&GameEvent::WonJackpot { player: 1, amount: 200 }));
+        assert_eq!(g.players[1].cash(), 1700);
+        assert_eq!(g.jackpot(), 0);
+
+        // without the house rule, taxes go to the bank
+        let mut g = game(vec!["A".to_string()], GameMode::Classic, rules::Rules::official());
+        g.apply(GameCommand::RollDice(1, 3));
+        assert_eq!(g.jackpot(), 0);
+    }
+
+    #[test]
+    fn double_releases_from_jail_without_rolling_again() {
+        let mut g = game(vec!["Jailbird".to_string()], GameMode::Classic, rules::Rules::official());
+        g.players[0].go_to_jail(10);
+        let events = g.apply(GameCommand::RollDice(2, 2));
+        assert_eq!(events[..2], [
+            GameEvent::LeftJail { player: 0, release: JailRelease::Double },
+            GameEvent::Moved { player: 0, from: 10, to: 14 }
+        ]);
+        assert_eq!(g.phase(), TurnPhase::Purchase(14));
+    }
+
     #[test]
     fn three_2_doubles_not_in_jail() {
-        let mut g = init(vec!["Test".to_string()]);
-        g.set_unit_test();
+        let mut g = game(vec!["Test".to_string()], GameMode::Classic, rules::Rules::official());
 
         // go to jail
         {
-            let p = g.players.get(0).unwrap().borrow();
+            let p = &g.players[0];
             assert_eq!(p.is_in_jail(), false);
         }
 
         let mut dice = Dice::new(2, 2);
         dice.reroll(Dice::new(2, 2));
         assert_eq!(dice.num_rolls, 2);
-        g.execute_turn(dice);
+        move_and_buy(&mut g, dice);
         {
-            let p = g.players.get(0).unwrap().borrow();
+            let p = &g.players[0];
             assert_eq!(p.is_in_jail(), false);
         }
     }
 
     #[test]
     fn three_doubles_in_jail() {
-        let mut g = init(vec!["Test".to_string()]);
-        g.set_unit_test();
+        let mut g = game(vec!["Test".to_string()], GameMode::Classic, rules::Rules::official());
 
         // go to jail
         {
-            let p = g.players.get(0).unwrap().borrow();
+            let p = &g.players[0];
             assert_eq!(p.is_in_jail(), false);
         }
 
@@ -1597,9 +2682,9 @@ mod tests {
         dice.reroll(Dice::new(2, 2));
         dice.reroll(Dice::new(2, 2));
         assert_eq!(dice.num_rolls, 3);
-        g.execute_turn(dice);
+        move_and_buy(&mut g, dice);
         {
-            let p = g.players.get(0).unwrap().borrow();
+            let p = &g.players[0];
             assert_eq!(p.is_in_jail(), true);
             assert_eq!(p.cash(), 1500);
         }
@@ -1607,271 +2692,258 @@ mod tests {
 
     #[test]
     fn player_in_jail_does_not_advance() {
-        let mut g = init(vec!["Jailbird".to_string()]);
-        g.set_unit_test();
+        let mut g = game(vec!["Jailbird".to_string()], GameMode::Classic, rules::Rules::official());
 
         // go to jail
         {
-            let mut p = g.players.get(0).unwrap().borrow_mut();
-            p.transact_cash(-1480);
+            let p = &mut g.players[0];
+            p.transact_cash(-1480).unwrap();
         }
         let mut dice = Dice::new(2, 2);
         dice.reroll(Dice::new(2, 2));
         dice.reroll(Dice::new(2, 2));
-        g.execute_turn(dice);
+        move_and_buy(&mut g, dice);
         {
-            let p = g.players.get(0).unwrap().borrow();
+            let p = &g.players[0];
             assert_eq!(p.is_in_jail(), true);
             assert_eq!(p.cash(), 20); // now can't pay $50 to be free from jail
         }
 
         // stay in jail when not rolling double or paying $50 to get free
-        g.execute_turn(Dice::new(3, 2));
+        move_and_buy(&mut g, Dice::new(3, 2));
         {
-            let p = g.players.get(0).unwrap().borrow();
+            let p = &g.players[0];
             assert_eq!(p.is_in_jail(), true);
             assert_eq!(p.position(), 10);
             assert_eq!(p.cash(), 20);
         }
 
         // player is free when rolling double
-        g.execute_turn(Dice::new(3, 3));
-        let p = g.players.get(0).unwrap().borrow();
+        move_and_buy(&mut g, Dice::new(3, 3));
+        let p = &g.players[0];
         assert_eq!(p.is_in_jail(), false);
         assert_eq!(p.position(), 16);
     }
 
     #[test]
     fn calculate_rent_unowned() {
-        let mut g = init(vec!["Test".to_string()]);
-        g.set_unit_test();
+        let g = game(vec!["Test".to_string()], GameMode::Classic, rules::Rules::official());
         // Unowned square | No Rent
         let s = g.board.get(1).unwrap();
-        let r = g.calculate_rent(s, Dice::new(0, 0));
+        let r = g.calculate_rent(s, Dice::new(0, 0), Rent::Standard);
         assert_eq!(r, None);
         
         // Income tax | No rent
         let s = g.board.get(4).unwrap();
-        let r = g.calculate_rent(s, Dice::new(0, 0));
+        let r = g.calculate_rent(s, Dice::new(0, 0), Rent::Standard);
         assert_eq!(r, None);
        
         // no-rent square
         let s = g.board.get(10).unwrap();
-        let r = g.calculate_rent(s, Dice::new(0, 0));
+        let r = g.calculate_rent(s, Dice::new(0, 0), Rent::Standard);
         assert_eq!(r, None);
     }
 
     #[test]
     fn calculate_rent_mortgaged() {
-        let mut g = init(vec!["StreetOwner".to_string()]);
-        g.set_unit_test();
-        let s = g.board.get(3).unwrap();
-        assert_eq!(s.asset.borrow().owner(), None);
+        let mut g = game(vec!["StreetOwner".to_string()], GameMode::Classic,
+                         rules::Rules::official());
+        assert_eq!(g.board[3].asset().owner(), None);
 
-        g.execute_turn(Dice::new(3, 0)); // Owner moves to Baltic Avenue
+        move_and_buy(&mut g, Dice::new(3, 0)); // Owner moves to Baltic Avenue
 
-        s.asset.borrow_mut().mortgage();
-        assert_eq!(g.calculate_rent(s, Dice::new(0, 0)), None);
-        s.asset.borrow_mut().unmortgage();
-        assert_eq!(g.calculate_rent(s, Dice::new(0, 0)), Some(4));
+        g.board[3].asset_mut().mortgage();
+        assert_eq!(g.calculate_rent(&g.board[3], Dice::new(0, 0), Rent::Standard), None);
+        g.board[3].asset_mut().unmortgage();
+        assert_eq!(g.calculate_rent(&g.board[3], Dice::new(0, 0), Rent::Standard), Some(4));
     }
 
     #[test]
     fn calculate_rent_street() {
-        let mut g = init(vec!["StreetOwner".to_string(),
-                          "StreetRenter".to_string()]);
-        g.set_unit_test();
+        let mut g = game(vec!["StreetOwner".to_string(),
+                          "StreetRenter".to_string()], GameMode::Classic, rules::Rules::official());
         let s = g.board.get(3).unwrap();
-        assert_eq!(s.asset.borrow().owner(), None);
+        assert_eq!(s.asset().owner(), None);
 
         // Buy the following squares:
         // Baltic Avenue[3] (1 of set of 2)
         // Oriental[6] & Vermont Ave[8] (2 of set of 3)
         // St. Charles place[11], States Ave[13], Virginia Ave[14] (3 of set of 3)
         // Park Place[37] & Boardwalk[39] (2 of set of 2)
-        g.execute_turn(Dice::new(3, 0)); // Owner moves to Baltic Avenue
-        g.execute_turn(Dice::new(3, 0)); // Owner moves to Oriental Avenue
-        g.execute_turn(Dice::new(2, 0)); // Vermont Avenue
-        g.execute_turn(Dice::new(3, 0)); // St. Charles place
-        g.execute_turn(Dice::new(2, 0)); // States Ave
-        g.execute_turn(Dice::new(1, 0)); // Virginia Ave
-        g.execute_turn(Dice::new(23, 0)); // Park place
-        g.execute_turn(Dice::new(2, 0)); // Boardwalk
+        move_and_buy(&mut g, Dice::new(3, 0)); // Owner moves to Baltic Avenue
+        move_and_buy(&mut g, Dice::new(3, 0)); // Owner moves to Oriental Avenue
+        move_and_buy(&mut g, Dice::new(2, 0)); // Vermont Avenue
+        move_and_buy(&mut g, Dice::new(3, 0)); // St. Charles place
+        move_and_buy(&mut g, Dice::new(2, 0)); // States Ave
+        move_and_buy(&mut g, Dice::new(1, 0)); // Virginia Ave
+        move_and_buy(&mut g, Dice::new(23, 0)); // Park place
+        move_and_buy(&mut g, Dice::new(2, 0)); // Boardwalk
 
         // Rent for 1 of 2 set 
         let s = g.board.get(3).unwrap(); // Baltic
-        let r = g.calculate_rent(s, Dice::new(0, 0));
+        let r = g.calculate_rent(s, Dice::new(0, 0), Rent::Standard);
         assert_eq!(r, Some(4));
 
         // Rent for 2 of 3 set 
         let s = g.board.get(6).unwrap(); // Oriental
-        let r = g.calculate_rent(s, Dice::new(0, 0));
+        let r = g.calculate_rent(s, Dice::new(0, 0), Rent::Standard);
         assert_eq!(r, Some(6));
         let s = g.board.get(8).unwrap(); // Vermont
-        let r = g.calculate_rent(s, Dice::new(0, 0));
+        let r = g.calculate_rent(s, Dice::new(0, 0), Rent::Standard);
         assert_eq!(r, Some(6));
 
         // rent for 3 of 3 set 
         let s = g.board.get(11).unwrap(); // St. Charles 
-        let r = g.calculate_rent(s, Dice::new(0, 0));
+        let r = g.calculate_rent(s, Dice::new(0, 0), Rent::Standard);
         assert_eq!(r, Some(20));
         let s = g.board.get(13).unwrap(); // States Ave
-        let r = g.calculate_rent(s, Dice::new(0, 0));
+        let r = g.calculate_rent(s, Dice::new(0, 0), Rent::Standard);
         assert_eq!(r, Some(20));
         let s = g.board.get(14).unwrap(); // Virginia Ave
-        let r = g.calculate_rent(s, Dice::new(0, 0));
+        let r = g.calculate_rent(s, Dice::new(0, 0), Rent::Standard);
         assert_eq!(r, Some(24));
         
         // rent for 2 of 2 set 
         let s = g.board.get(37).unwrap(); // Park Ave
-        let r = g.calculate_rent(s, Dice::new(0, 0));
+        let r = g.calculate_rent(s, Dice::new(0, 0), Rent::Standard);
         assert_eq!(r, Some(70));
         let s = g.board.get(39).unwrap(); // Boardwalk
-        let r = g.calculate_rent(s, Dice::new(0, 0));
+        let r = g.calculate_rent(s, Dice::new(0, 0), Rent::Standard);
         assert_eq!(r, Some(100));
     }
 
     #[test]
     fn calculate_rent_utility() {
         // Buy 1 utility, then buy the second
-        let mut g = init(vec!["TestOwner".to_string(), "TestRenter".to_string()]);
-        g.set_unit_test();
+        let mut g = game(vec!["TestOwner".to_string(), "TestRenter".to_string()], GameMode::Classic,
+                         rules::Rules::official());
 
-        g.execute_turn(Dice::new(12, 0)); // Electric
+        move_and_buy(&mut g, Dice::new(12, 0)); // Electric
         let s = g.board.get(12).unwrap(); // Electric
-        let r = g.calculate_rent(s, Dice::new(1, 2));
+        let r = g.calculate_rent(s, Dice::new(1, 2), Rent::Standard);
         assert_eq!(r, Some(12));  // 3 * 4
 
-        g.execute_turn(Dice::new(16, 0)); // Water
+        move_and_buy(&mut g, Dice::new(16, 0)); // Water
         let s = g.board.get(28).unwrap();
-        let r = g.calculate_rent(s, Dice::new(3, 0));
+        let r = g.calculate_rent(s, Dice::new(3, 0), Rent::Standard);
         assert_eq!(r, Some(30)); // 3 * 10
 
         // cumulative roll
         let mut dice = Dice::new(3, 0);
         dice.reroll(Dice::new(6, 0)); // cumulative sum is 9
-        let r = g.calculate_rent(s, dice);
+        let r = g.calculate_rent(s, dice, Rent::Standard);
         assert_eq!(r, Some(90)); // 9 * 10
     }
 
     #[test]
     fn calculate_rent_station() {
         // Buy stations one at a time
-        let mut g = init(vec!["StationOwner".to_string(),
-                              "StationRenter".to_string()]);
-        g.set_unit_test();
+        let mut g = game(vec!["StationOwner".to_string(),
+                              "StationRenter".to_string()], GameMode::Classic,
+                              rules::Rules::official());
 
-        g.execute_turn(Dice::new(5, 0)); // Reading Railroad
+        move_and_buy(&mut g, Dice::new(5, 0)); // Reading Railroad
         let s = g.board.get(5).unwrap();
-        let r = g.calculate_rent(s, Dice::new(0, 0));
+        let r = g.calculate_rent(s, Dice::new(0, 0), Rent::Standard);
         assert_eq!(r, Some(25)); 
 
-        g.execute_turn(Dice::new(10, 0)); // Pennsylvania Railroad
+        move_and_buy(&mut g, Dice::new(10, 0)); // Pennsylvania Railroad
         let s = g.board.get(5).unwrap();
-        let r = g.calculate_rent(s, Dice::new(0, 0));
+        let r = g.calculate_rent(s, Dice::new(0, 0), Rent::Standard);
         assert_eq!(r, Some(50)); 
 
-        g.execute_turn(Dice::new(10, 0)); // B.O. Railroad
+        move_and_buy(&mut g, Dice::new(10, 0)); // B.O. Railroad
         let s = g.board.get(5).unwrap();
-        let r = g.calculate_rent(s, Dice::new(0, 0));
+        let r = g.calculate_rent(s, Dice::new(0, 0), Rent::Standard);
         assert_eq!(r, Some(100)); 
 
-        g.execute_turn(Dice::new(10, 0)); // Short line
+        move_and_buy(&mut g, Dice::new(10, 0)); // Short line
         let s = g.board.get(5).unwrap();
-        let r = g.calculate_rent(s, Dice::new(0, 0));
+        let r = g.calculate_rent(s, Dice::new(0, 0), Rent::Standard);
         assert_eq!(r, Some(200)); 
     }
 
     #[test]
     fn purchase_and_pay_rent() {
-        let mut g = init(vec!["Owner".to_string(), "Renter".to_string()]);
-        g.set_unit_test();
-        let s = g.board.get(3).unwrap();
-        assert_eq!(s.asset.borrow().owner(), None);
+        let mut g = game(vec!["Owner".to_string(), "Renter".to_string()], GameMode::Classic,
+                         rules::Rules::official());
+        assert_eq!(g.board[3].asset().owner(), None);
 
-        g.execute_turn(Dice::new(3, 0)); // Owner moves to Baltic Avenue
+        move_and_buy(&mut g, Dice::new(3, 0)); // Owner moves to Baltic Avenue
         {
-            let owner = g.players.get(0).unwrap().borrow();
+            let owner = &g.players[0];
             assert_eq!(owner.cash(), 1440); // bought street
-            assert_eq!(s.asset.borrow().owner().unwrap(), owner.turn_idx());
+            assert_eq!(g.board[3].asset().owner().unwrap(), owner.turn_idx());
         }
 
         g.set_active_player(1);
-        g.execute_turn(Dice::new(3, 0)); // Renter on Baltic Ave
-        let renter = g.players.get(1).unwrap().borrow();
+        move_and_buy(&mut g, Dice::new(3, 0)); // Renter on Baltic Ave
+        let renter = &g.players[1];
         assert_eq!(renter.cash(), 1496);
-        let owner = g.players.get(0).unwrap().borrow();
+        let owner = &g.players[0];
         assert_eq!(owner.cash(), 1444);
     }
 
     #[test]
     fn buy_each_property_type() {
-        let mut g = init(vec!["Mongul".to_string()]);
-        g.set_unit_test();
-        let s = g.board.get(3).unwrap();
-        assert_eq!(s.asset.borrow().owner(), None);
+        let mut g = game(vec!["Mongul".to_string()], GameMode::Classic, rules::Rules::official());
+        assert_eq!(g.board[3].asset().owner(), None);
 
-        g.execute_turn(Dice::new(3, 0)); // Mongul moves to Baltic Avenue
+        move_and_buy(&mut g, Dice::new(3, 0)); // Mongul moves to Baltic Avenue
         {
-            let p = g.players.get(0).unwrap().borrow();
+            let p = &g.players[0];
             assert_eq!(p.cash(), 1440); // bought street
-            assert_eq!(s.asset.borrow().owner().unwrap(), p.turn_idx());
+            assert_eq!(g.board[3].asset().owner().unwrap(), p.turn_idx());
         }
 
-        g.execute_turn(Dice::new(9, 0)); // Mongul moves to Electric Company
+        move_and_buy(&mut g, Dice::new(9, 0)); // Mongul moves to Electric Company
         {
-            let p = g.players.get(0).unwrap().borrow();
+            let p = &g.players[0];
             assert_eq!(p.cash(), 1290); // bought street
-            assert_eq!(s.asset.borrow().owner().unwrap(), p.turn_idx());
+            assert_eq!(g.board[12].asset().owner().unwrap(), p.turn_idx());
         }
 
-        g.execute_turn(Dice::new(3, 0)); // Mongul moves to Pennsylvania Railroad
-        let p = g.players.get(0).unwrap().borrow();
+        move_and_buy(&mut g, Dice::new(3, 0)); // Mongul moves to Pennsylvania Railroad
+        let p = &g.players[0];
         assert_eq!(p.cash(), 1090); // bought street
-        assert_eq!(s.asset.borrow().owner().unwrap(), p.turn_idx());
+        assert_eq!(g.board[15].asset().owner().unwrap(), p.turn_idx());
     }
 
     #[test]
     fn sell_a_property() {
-        let mut g = init(vec!["Seller".to_string(), "NewOwner".to_string()]);
-        g.set_unit_test();
-        let s = g.board.get(3).unwrap();
-        assert_eq!(s.asset.borrow().owner(), None);
-
-        g.execute_turn(Dice::new(3, 0)); // Seller moves to Baltic Avenue
-        let mut seller = g.players.get(0).unwrap().borrow_mut();
-        assert_eq!(seller.cash(), 1440); // bought street
-        assert_eq!(s.asset.borrow().owner().unwrap(), seller.turn_idx());
-
-        let mut owner = g.players.get(1).unwrap().borrow_mut();
-        g.sell_property(&mut seller, &mut owner, &s, 20);
-        assert_eq!(owner.cash(), 1480);
-        assert_eq!(seller.cash(), 1460);
-        assert_eq!(s.asset.borrow().owner().unwrap(), owner.turn_idx());
+        let mut g = game(vec!["Seller".to_string(), "NewOwner".to_string()], GameMode::Classic,
+                         rules::Rules::official());
+        assert_eq!(g.board[3].asset().owner(), None);
+
+        move_and_buy(&mut g, Dice::new(3, 0)); // Seller moves to Baltic Avenue
+        assert_eq!(g.players[0].cash(), 1440); // bought street
+        assert_eq!(g.board[3].asset().owner().unwrap(), 0);
+
+        assert_eq!(g.sell_property(0, 1, 3, 20), Ok(()));
+        assert_eq!(g.players[1].cash(), 1480);
+        assert_eq!(g.players[0].cash(), 1460);
+        assert_eq!(g.board[3].asset().owner().unwrap(), 1);
     }
 
     #[test]
     fn mortgage_then_unmortgage() {
-        let mut g = init(vec!["M".to_string()]);
-        g.set_unit_test();
+        let mut g = game(vec!["M".to_string()], GameMode::Classic, rules::Rules::official());
         let street_idx: usize = 3;
-        let s = g.board.get(street_idx).unwrap();
-        assert_eq!(s.asset.borrow().owner(), None);
+        assert_eq!(g.board[street_idx].asset().owner(), None);
 
-        g.execute_turn(Dice::new(3, 0)); // Seller moves to Baltic Avenue
-        let mut owner = g.players.get(0).unwrap().borrow_mut();
-        assert_eq!(owner.cash(), 1440); // bought street
-        assert_eq!(s.asset.borrow().owner().unwrap(), owner.turn_idx());
+        move_and_buy(&mut g, Dice::new(3, 0)); // Seller moves to Baltic Avenue
+        assert_eq!(g.players[0].cash(), 1440); // bought street
+        assert_eq!(g.board[street_idx].asset().owner().unwrap(), 0);
 
-        actions::mortgage_street(&g, &mut owner, street_idx);
-        assert_eq!(owner.cash(), 1470); // mortgage of 30
-        assert_eq!(s.asset.borrow().owner().unwrap(), owner.turn_idx());
-        assert_eq!(s.asset.borrow().is_mortgaged(), true);
+        assert_eq!(actions::mortgage_street(&mut g, 0, street_idx), Ok(()));
+        assert_eq!(g.players[0].cash(), 1470); // mortgage of 30
+        assert_eq!(g.board[street_idx].asset().owner().unwrap(), 0);
+        assert_eq!(g.board[street_idx].asset().is_mortgaged(), true);
 
-        actions::unmortgage_street(&g, &mut owner, street_idx);
-        assert_eq!(owner.cash(), 1437); // unmortgage for 33
-        assert_eq!(s.asset.borrow().owner().unwrap(), owner.turn_idx());
-        assert_eq!(s.asset.borrow().is_mortgaged(), false);
+        assert_eq!(actions::unmortgage_street(&mut g, 0, street_idx), Ok(()));
+        assert_eq!(g.players[0].cash(), 1437); // unmortgage for 33
+        assert_eq!(g.board[street_idx].asset().owner().unwrap(), 0);
+        assert_eq!(g.board[street_idx].asset().is_mortgaged(), false);
     }
 
     #[test]
@@ -1883,7 +2955,7 @@ mod tests {
         cards.push(card::Card::new("Card 4", card::CardAction::Payment, Some(100), None));
         cards.push(card::Card::new("Card 5", card::CardAction::Payment, Some(-200), None));
         cards.push(card::Card::new("Card 6", card::CardAction::Payment, Some(-80), None));
-        shuffle_cards(&mut cards);
+        shuffle_cards(&mut cards, &mut StdRng::seed_from_u64(7));
         assert_eq!(cards.len(), 6);
         assert_eq!(false, // there must be a change in order
             cards.get(0).unwrap().description() == "Card 1" &&
@@ -1897,249 +2969,402 @@ mod tests {
 
     #[test]
     fn unmortgage_cost_calculation() {
-        assert_eq!(33, square::StreetDetails::new('B', 60, 2, [4,0,0,0,0,0], 30)
+        assert_eq!(33, square::StreetDetails::new(None, 60, 2, [4,0,0,0,0,0], 30)
                    .get_unmortgage_amount());
-        assert_eq!(193, square::StreetDetails::new('B', 60, 2, [4,0,0,0,0,0], 175)
+        assert_eq!(193, square::StreetDetails::new(None, 60, 2, [4,0,0,0,0,0], 175)
                    .get_unmortgage_amount());
-        assert_eq!(220, square::StreetDetails::new('B', 60, 2, [4,0,0,0,0,0], 200)
+        assert_eq!(220, square::StreetDetails::new(None, 60, 2, [4,0,0,0,0,0], 200)
                    .get_unmortgage_amount());
     }
 
     #[test]
     fn buy_first_house() {
         // cannot buy house unless all houses in suburb are owned 
-        let mut g = init(vec!["Tycoon".to_string()]);
-        g.set_unit_test();
+        let mut g = game(vec!["Tycoon".to_string()], GameMode::Classic, rules::Rules::official());
         let street_idx: usize = 1;
-        let s = g.board.get(street_idx).unwrap();
-        assert_eq!(s.asset.borrow().owner(), None);
+        assert_eq!(g.board[street_idx].asset().owner(), None);
 
-        g.execute_turn(Dice::new(1, 0)); // Seller moves to Mediterranean Avenue
-        {
-            let mut owner = g.players.get(0).unwrap().borrow_mut();
-            assert_eq!(owner.cash(), 1440); // bought street
-            actions::buy_house(&g, &mut owner, street_idx); // whole suburb isn't owned
-            assert_eq!(s.asset.borrow().house_num(), 0);
-        }
+        move_and_buy(&mut g, Dice::new(1, 0)); // Seller moves to Mediterranean Avenue
+        assert_eq!(g.players[0].cash(), 1440); // bought street
+        assert!(actions::buy_house(&mut g, 0, street_idx).is_err()); // whole suburb isn't owned
+        assert_eq!(g.board[street_idx].asset().house_num(), 0);
 
-        g.execute_turn(Dice::new(2, 0)); // Seller moves to Baltic Avenue and buys it
-        let mut owner = g.players.get(0).unwrap().borrow_mut();
-        assert_eq!(owner.cash(), 1380); // bought street
-        actions::buy_house(&g, &mut owner, street_idx); // buy Mediterranean
-        assert_eq!(s.asset.borrow_mut().house_num(), 1);
-        assert_eq!(owner.cash(), 1330); // bought house for 50
+        move_and_buy(&mut g, Dice::new(2, 0)); // Seller moves to Baltic Avenue and buys it
+        assert_eq!(g.players[0].cash(), 1380); // bought street
+        assert_eq!(actions::buy_house(&mut g, 0, street_idx), Ok(())); // buy Mediterranean
+        assert_eq!(g.board[street_idx].asset().house_num(), 1);
+        assert_eq!(g.players[0].cash(), 1330); // bought house for 50
 
         // cannot buy second house on mediterranean
-        actions::buy_house(&g, &mut owner, street_idx);
-        assert_eq!(s.asset.borrow_mut().house_num(), 1);
-        assert_eq!(owner.cash(), 1330); // bought house for 50
+        assert!(actions::buy_house(&mut g, 0, street_idx).is_err());
+        assert_eq!(g.board[street_idx].asset().house_num(), 1);
+        assert_eq!(g.players[0].cash(), 1330); // bought house for 50
 
         // sell house
-        actions::sell_house(&g, &mut owner, street_idx);
-        assert_eq!(s.asset.borrow_mut().house_num(), 0);
-        assert_eq!(owner.cash(), 1380); // sell house for 50
+        assert_eq!(actions::sell_house(&mut g, 0, street_idx), Ok(()));
+        assert_eq!(g.board[street_idx].asset().house_num(), 0);
+        assert_eq!(g.players[0].cash(), 1380); // sell house for 50
     }
 
     #[test]
     fn buy_houses_then_hotel() {
         // buy all possible houses for suburb
-        let mut g = init(vec!["Tycoon".to_string()]);
-        g.set_unit_test();
+        let mut g = game(vec!["Tycoon".to_string()], GameMode::Classic, rules::Rules::official());
         let street_idx: usize = 1;
-        let s = g.board.get(street_idx).unwrap();
-        assert_eq!(s.asset.borrow().owner(), None);
-        let rs = s.get_street_details().unwrap().rent_suburb();
+        assert_eq!(g.board[street_idx].asset().owner(), None);
+        let rs = g.board[street_idx].get_street_details().unwrap().rent_suburb().to_vec();
 
-        g.execute_turn(Dice::new(1, 0)); // Seller moves to Mediterranean Avenue
-        {
-            let owner = g.players.get(0).unwrap().borrow();
-            assert_eq!(owner.cash(), 1440); // bought street
-            assert_eq!(g.calculate_rent(s, Dice::new(3, 0)).unwrap(),
-                       2); // rent for Mediterranean w/o suburb
-        }
+        move_and_buy(&mut g, Dice::new(1, 0)); // Seller moves to Mediterranean Avenue
+        assert_eq!(g.players[0].cash(), 1440); // bought street
+        assert_eq!(g.calculate_rent(&g.board[street_idx], Dice::new(3, 0), Rent::Standard).unwrap(),
+                   2); // rent for Mediterranean w/o suburb
 
-        g.execute_turn(Dice::new(2, 0)); // Seller moves to Baltic Avenue
-        {
-            let owner = g.players.get(0).unwrap().borrow();
-            assert_eq!(owner.cash(), 1380); // bought street
-            assert_eq!(g.calculate_rent(s, Dice::new(3, 0)).unwrap(),
-                       rs[0]); // rent for suburb
-        }
+        move_and_buy(&mut g, Dice::new(2, 0)); // Seller moves to Baltic Avenue
+        assert_eq!(g.players[0].cash(), 1380); // bought street
+        assert_eq!(g.calculate_rent(&g.board[street_idx], Dice::new(3, 0), Rent::Standard).unwrap(),
+                   rs[0]); // rent for suburb
 
         // tycoon now owns all streets, lets iteratively buy houses up to 4
-        let mut owner = g.players.get(0).unwrap().borrow_mut();
         for i in 0..4 {
             // buy another house in Mediterranean then Baltic
             let street_idx = 1;
-            let s = g.board.get(street_idx).unwrap();
-            actions::buy_house(&g, &mut owner, street_idx);
-            assert_eq!(s.asset.borrow().house_num(), (i+1));
+            assert_eq!(actions::buy_house(&mut g, 0, street_idx), Ok(()));
+            assert_eq!(g.board[street_idx].asset().house_num(), (i+1));
             let r: u32 = rs[(i+1) as usize];
-            assert_eq!(g.calculate_rent(s, Dice::new(3, 0)).unwrap(),
+            assert_eq!(g.calculate_rent(&g.board[street_idx], Dice::new(3, 0), Rent::Standard).unwrap(),
                        r); // rent for houses
 
             let street_idx = 3;
-            let s = g.board.get(street_idx).unwrap();
-            actions::buy_house(&g, &mut owner, street_idx);
-            assert_eq!(s.asset.borrow().house_num(), (i+1));
+            assert_eq!(actions::buy_house(&mut g, 0, street_idx), Ok(()));
+            let s = &g.board[street_idx];
+            assert_eq!(s.asset().house_num(), (i+1));
 
             // not eligible for hotel
-            if s.asset.borrow().house_num() != 4 {
+            if s.asset().house_num() != 4 {
                 assert_eq!(g.street_eligible_for_hotel(&s), false);
             }
         }
 
         // double check, both streets should have 4 houses
-        assert_eq!(g.board.get(1).unwrap().asset.borrow().house_num(), 4);
-        assert_eq!(g.board.get(3).unwrap().asset.borrow().house_num(), 4);
+        assert_eq!(g.board[1].asset().house_num(), 4);
+        assert_eq!(g.board[3].asset().house_num(), 4);
 
         // buying 5th house fails
         let street_idx = 1;
-        let s = g.board.get(street_idx).unwrap();
-        actions::buy_house(&g, &mut owner, street_idx);
-        assert_eq!(s.asset.borrow().house_num(), 4);
-        assert_eq!(s.asset.borrow().has_hotel(), false);
+        assert!(actions::buy_house(&mut g, 0, street_idx).is_err());
+        assert_eq!(g.board[street_idx].asset().house_num(), 4);
+        assert_eq!(g.board[street_idx].asset().has_hotel(), false);
 
         // buying hotel succeeds
-        actions::buy_hotel(&g, &mut owner, street_idx);
-        assert_eq!(s.asset.borrow().house_num(), 4);
-        assert_eq!(s.asset.borrow().has_hotel(), true);
+        assert_eq!(actions::buy_hotel(&mut g, 0, street_idx), Ok(()));
+        assert_eq!(g.board[street_idx].asset().house_num(), 4);
+        assert_eq!(g.board[street_idx].asset().has_hotel(), true);
     }
 
     #[test]
     fn buy_hotels_fails() {
         // buy all possible houses for suburb
-        let mut g = init(vec!["Tycoon".to_string()]);
-        g.set_unit_test();
+        let mut g = game(vec!["Tycoon".to_string()], GameMode::Classic, rules::Rules::official());
         let street_idx: usize = 1;
-        let s = g.board.get(street_idx).unwrap();
-        assert_eq!(s.asset.borrow().owner(), None);
+        assert_eq!(g.board[street_idx].asset().owner(), None);
 
         // buy Brown suburb
-        g.execute_turn(Dice::new(1, 0)); // Seller moves to Mediterranean Avenue
+        move_and_buy(&mut g, Dice::new(1, 0)); // Seller moves to Mediterranean Avenue
 
-        {
-            // try buy hotel
-            let mut owner = g.players.get(0).unwrap().borrow_mut();
-            actions::buy_hotel(&g, &mut owner, street_idx);
-            assert_eq!(s.asset.borrow().house_num(), 0);
-            assert_eq!(s.asset.borrow().has_hotel(), false);
-        }
+        // try buy hotel
+        assert!(actions::buy_hotel(&mut g, 0, street_idx).is_err());
+        assert_eq!(g.board[street_idx].asset().house_num(), 0);
+        assert_eq!(g.board[street_idx].asset().has_hotel(), false);
 
-        g.execute_turn(Dice::new(2, 0)); // Seller moves to Baltic Avenue
+        move_and_buy(&mut g, Dice::new(2, 0)); // Seller moves to Baltic Avenue
         // try buy hotel
-        let mut owner = g.players.get(0).unwrap().borrow_mut();
-        actions::buy_hotel(&g, &mut owner, 3);
-        assert_eq!(s.asset.borrow().house_num(), 0);
-        assert_eq!(s.asset.borrow().has_hotel(), false);
+        assert!(actions::buy_hotel(&mut g, 0, 3).is_err());
+        assert_eq!(g.board[street_idx].asset().house_num(), 0);
+        assert_eq!(g.board[street_idx].asset().has_hotel(), false);
     }
 
     #[test]
     fn buy_then_sell_hotel() {
         // buy all possible houses for suburb
-        let mut g = init(vec!["Tycoon".to_string()]);
-        g.set_unit_test();
-        let street_idx: usize = 1;
+        let mut g = game(vec!["Tycoon".to_string()], GameMode::Classic, rules::Rules::official());
 
         // buy brown squares
-        g.execute_turn(Dice::new(1, 0));
-        g.execute_turn(Dice::new(2, 0));
+        move_and_buy(&mut g, Dice::new(1, 0));
+        move_and_buy(&mut g, Dice::new(2, 0));
 
         // put houses on all squares
-        let mut owner = g.players.get(0).unwrap().borrow_mut();
         for _ in 0..4 {
             // buy another house in Mediterranean then Baltic
             let street_idx = 1;
-            actions::buy_house(&g, &mut owner, street_idx);
+            assert_eq!(actions::buy_house(&mut g, 0, street_idx), Ok(()));
 
             let street_idx = 3;
-            actions::buy_house(&g, &mut owner, street_idx);
+            assert_eq!(actions::buy_house(&mut g, 0, street_idx), Ok(()));
         }
 
         // double check, both streets should have 4 houses
-        assert_eq!(g.board.get(1).unwrap().asset.borrow().house_num(), 4);
-        assert_eq!(g.board.get(3).unwrap().asset.borrow().house_num(), 4);
+        assert_eq!(g.board[1].asset().house_num(), 4);
+        assert_eq!(g.board[3].asset().house_num(), 4);
 
         // buying hotels succeeds
         let street_idx = 1;
-        let s = g.board.get(street_idx).unwrap();
-        actions::buy_hotel(&g, &mut owner, street_idx);
-        assert_eq!(s.asset.borrow().has_hotel(), true);
-        assert_eq!(g.calculate_rent(s, Dice::new(3, 0)).unwrap(), 250);
+        assert_eq!(actions::buy_hotel(&mut g, 0, street_idx), Ok(()));
+        assert_eq!(g.board[street_idx].asset().has_hotel(), true);
+        assert_eq!(g.calculate_rent(&g.board[street_idx], Dice::new(3, 0), Rent::Standard).unwrap(), 250);
 
         let street_idx = 3;
-        let s = g.board.get(street_idx).unwrap();
-        actions::buy_hotel(&g, &mut owner, street_idx);
-        assert_eq!(s.asset.borrow().has_hotel(), true);
-        assert_eq!(g.calculate_rent(s, Dice::new(3, 0)).unwrap(), 450);
+        assert_eq!(actions::buy_hotel(&mut g, 0, street_idx), Ok(()));
+        assert_eq!(g.board[street_idx].asset().has_hotel(), true);
+        assert_eq!(g.calculate_rent(&g.board[street_idx], Dice::new(3, 0), Rent::Standard).unwrap(), 450);
 
         // Selling hotels succeeds
-        actions::sell_hotel(&g, &mut owner, street_idx);
-        assert_eq!(s.asset.borrow().has_hotel(), false);
-        assert_eq!(g.calculate_rent(s, Dice::new(3, 0)).unwrap(), 320);
+        assert_eq!(actions::sell_hotel(&mut g, 0, street_idx), Ok(()));
+        assert_eq!(g.board[street_idx].asset().has_hotel(), false);
+        assert_eq!(g.calculate_rent(&g.board[street_idx], Dice::new(3, 0), Rent::Standard).unwrap(), 320);
 
         let street_idx = 1;
-        let s = g.board.get(street_idx).unwrap();
-        actions::sell_hotel(&g, &mut owner, street_idx);
-        assert_eq!(s.asset.borrow().has_hotel(), false);
-        assert_eq!(g.calculate_rent(s, Dice::new(3, 0)).unwrap(), 160);
+        assert_eq!(actions::sell_hotel(&mut g, 0, street_idx), Ok(()));
+        assert_eq!(g.board[street_idx].asset().has_hotel(), false);
+        assert_eq!(g.calculate_rent(&g.board[street_idx], Dice::new(3, 0), Rent::Standard).unwrap(), 160);
+    }
+
+    /// Give a player the streets, with the given number of houses on each
+    fn build_streets(g: &mut Game, owner: usize, streets: &[usize], houses: u32) {
+        for &s in streets {
+            g.board[s].asset_mut().set_owner(owner);
+            for _ in 0..houses {
+                g.board[s].asset_mut().buy_house().unwrap();
+            }
+        }
+    }
+
+    #[test]
+    fn bank_runs_out_of_buildings() {
+        let mut g = game(vec!["Tycoon".to_string()], GameMode::Classic, rules::Rules::official());
+        assert_eq!((g.houses_left(), g.hotels_left()), (32, 12));
+
+        // brown, light blue and pink streets use all 32 houses
+        build_streets(&mut g, 0, &[1, 3, 6, 8, 9, 11, 13, 14], 4);
+        build_streets(&mut g, 0, &[16, 18, 19], 0);
+        assert_eq!(g.houses_left(), 0);
+        assert_eq!(actions::buy_house(&mut g, 0, 16),
+                   Err(GameError::NotEnoughHouses { needed: 1, available: 0 }));
+
+        // buying a hotel returns the 4 houses of the street
+        assert_eq!(actions::buy_hotel(&mut g, 0, 1), Ok(()));
+        assert_eq!((g.houses_left(), g.hotels_left()), (4, 11));
+
+        // a hotel is only broken down when the bank has 4 houses for it
+        assert_eq!(actions::buy_house(&mut g, 0, 16), Ok(()));
+        assert_eq!(actions::sell_hotel(&mut g, 0, 1),
+                   Err(GameError::NotEnoughHouses { needed: 4, available: 3 }));
+        assert_eq!(g.board[1].asset().has_hotel(), true);
+        assert_eq!(actions::sell_house(&mut g, 0, 16), Ok(()));
+        assert_eq!(actions::sell_hotel(&mut g, 0, 1), Ok(()));
+        assert_eq!((g.houses_left(), g.hotels_left()), (0, 12));
+    }
+
+    #[test]
+    fn bank_runs_out_of_hotels() {
+        let mut g = game(vec!["Tycoon".to_string()], GameMode::Classic, rules::Rules::official());
+        build_streets(&mut g, 0, &[1, 3, 6, 8, 9, 11, 13, 14, 16, 18, 19, 21], 4);
+        for s in [1, 3, 6, 8, 9, 11, 13, 14, 16, 18, 19, 21].iter() {
+            g.board[*s].asset_mut().buy_hotel().unwrap();
+        }
+        build_streets(&mut g, 0, &[23, 24], 4);
+        assert_eq!(g.hotels_left(), 0);
+        assert_eq!(actions::buy_hotel(&mut g, 0, 23), Err(GameError::NoHotelsLeft));
+        assert_eq!(g.board[23].asset().has_hotel(), false);
+    }
+
+    #[test]
+    fn last_houses_are_auctioned() {
+        let mut g = game(vec!["A".to_string(), "B".to_string()], GameMode::Classic,
+                         rules::Rules::official());
+        build_streets(&mut g, 0, &[6, 8, 9, 11, 13, 14, 16, 18, 19], 3);
+        build_streets(&mut g, 0, &[21, 23, 24], 1);
+        g.board[21].asset_mut().buy_house().unwrap();
+        build_streets(&mut g, 1, &[1, 3], 0);
+        assert_eq!(g.houses_left(), 1);
+
+        // both players want the last house
+        g.set_phase(TurnPhase::Actions);
+        assert!(g.house_shortage());
+        let events = g.apply(GameCommand::BuyHouse { street: 1 });
+        assert_eq!(events, vec![GameEvent::Rejected { error: GameError::HouseShortage }]);
+        let events = g.apply(GameCommand::AuctionHouse);
+        assert_eq!(events, vec![GameEvent::HouseAuctionStarted { opening_bid: 50 }]);
+        assert_eq!(g.auction().unwrap().bidders(), &[0, 1]);
+        let events = g.apply(GameCommand::Bid { amount: 40 });
+        assert_eq!(events, vec![GameEvent::Rejected {
+            error: GameError::BidTooLow { minimum: 50 } }]);
+        g.apply(GameCommand::Bid { amount: 60 });
+        g.apply(GameCommand::Bid { amount: 80 });
+        assert_eq!(g.apply(GameCommand::PassBid), vec![
+            GameEvent::PassedBid { player: 0 },
+            GameEvent::WonHouse { player: 1, price: 80 }]);
+        assert_eq!(g.phase(), TurnPhase::Actions);
+
+        // the winner builds the house before anyone else, and before the turn ends
+        assert_eq!(g.apply(GameCommand::EndTurn),
+                   vec![GameEvent::Rejected { error: GameError::HouseBidPending }]);
+        assert_eq!(g.apply(GameCommand::BuyHouse { street: 23 }),
+                   vec![GameEvent::Rejected { error: GameError::HouseBidPending }]);
+        let events = g.apply(GameCommand::BuyHouse { street: 1 });
+        assert_eq!(events, vec![GameEvent::BoughtHouse { player: 1, square: 1, price: 80 }]);
+        assert_eq!(g.players[1].cash(), 1420);
+        assert_eq!(g.houses_left(), 0);
+        assert!(!g.house_shortage());
+        assert_eq!(g.apply(GameCommand::AuctionHouse),
+                   vec![GameEvent::Rejected { error: GameError::NoHouseShortage }]);
+    }
+
+    #[test]
+    fn trade_hands_over_everything_at_once() {
+        let mut g = game(vec!["A".to_string(), "B".to_string()], GameMode::Classic,
+                         rules::Rules::official());
+        build_streets(&mut g, 0, &[1, 3, 5], 0);
+        build_streets(&mut g, 1, &[6], 0);
+        g.board[5].asset_mut().mortgage();
+        g.players[0].receive_jail_free_card(card::Deck::Chance, jail_card());
+
+        let trade = trade::Trade {
+            proposer: 0,
+            counterparty: 1,
+            gives: trade::TradeOffer { streets: vec![1, 3, 5], cash: 0, jail_cards: 1 },
+            receives: trade::TradeOffer { streets: vec![6], cash: 100, jail_cards: 0 }
+        };
+        let events = g.apply(GameCommand::ProposeTrade { trade: trade.clone() });
+        assert_eq!(events, vec![GameEvent::TradeProposed { trade: trade.clone() }]);
+        assert_eq!(g.trade(), Some(&trade));
+        assert_eq!(g.board[1].asset().owner(), Some(0)); // nothing changed hands yet
+
+        let events = g.apply(GameCommand::AcceptTrade);
+        assert_eq!(events, vec![
+            GameEvent::Traded { trade },
+            GameEvent::PaidInterest { player: 1, square: 5, amount: 10 }
+        ]);
+        assert_eq!(g.trade(), None);
+        for s in [1, 3, 5].iter() {
+            assert_eq!(g.board[*s].asset().owner(), Some(1));
+        }
+        assert_eq!(g.board[6].asset().owner(), Some(0));
+        assert_eq!(g.players[0].cash(), 1600);
+        assert_eq!(g.players[1].cash(), 1390); // paid 10% of the mortgage of Reading
+        assert_eq!(g.players[0].num_get_out_of_jail_cards(), 0);
+        assert_eq!(g.players[1].num_get_out_of_jail_cards(), 1);
+    }
+
+    #[test]
+    fn trade_is_checked_before_anything_changes_hands() {
+        let mut g = game(vec!["A".to_string(), "B".to_string()], GameMode::Classic,
+                         rules::Rules::official());
+        build_streets(&mut g, 0, &[1, 3], 0);
+        build_streets(&mut g, 1, &[6], 0);
+        let offer = |streets: Vec<usize>, cash: u32| trade::TradeOffer {
+            streets, cash, jail_cards: 0 };
+        let propose = |g: &mut Game, gives, receives| g.apply(GameCommand::ProposeTrade {
+            trade: trade::Trade { proposer: 0, counterparty: 1, gives, receives } }).pop();
+        let rejected = |error| Some(GameEvent::Rejected { error });
+
+        assert_eq!(g.apply(GameCommand::AcceptTrade).pop(), rejected(GameError::NoTrade));
+        assert_eq!(propose(&mut g, offer(vec![], 0), offer(vec![], 0)),
+                   rejected(GameError::EmptyTrade));
+        assert_eq!(propose(&mut g, offer(vec![6], 0), offer(vec![], 0)),
+                   rejected(GameError::NotOwner));
+        assert_eq!(propose(&mut g, offer(vec![1, 1], 0), offer(vec![], 0)),
+                   rejected(GameError::DuplicateStreet));
+        assert_eq!(propose(&mut g, offer(vec![], 1600), offer(vec![6], 0)),
+                   rejected(GameError::InsufficientFunds { needed: 1600, available: 1500 }));
+        assert_eq!(g.trade(), None);
+
+        // a house on the suburb blocks the trade, even after it was proposed
+        assert_eq!(propose(&mut g, offer(vec![1], 0), offer(vec![6], 0)).is_some(), true);
+        assert_eq!(actions::buy_house(&mut g, 0, 3), Ok(()));
+        assert_eq!(g.apply(GameCommand::AcceptTrade).pop(), rejected(GameError::HasBuildings));
+        assert_eq!(g.board[1].asset().owner(), Some(0));
+        assert_eq!(g.board[6].asset().owner(), Some(1));
+        assert!(g.trade().is_some());
+    }
+
+    #[test]
+    fn counter_then_reject_trade() {
+        let mut g = game(vec!["A".to_string(), "B".to_string()], GameMode::Classic,
+                         rules::Rules::official());
+        build_streets(&mut g, 0, &[1], 0);
+        let trade = trade::Trade {
+            proposer: 0,
+            counterparty: 1,
+            gives: trade::TradeOffer { streets: vec![1], ..Default::default() },
+            receives: trade::TradeOffer { cash: 50, ..Default::default() }
+        };
+        g.apply(GameCommand::ProposeTrade { trade: trade.clone() });
+
+        // B offers more cash instead
+        let events = g.apply(GameCommand::CounterTrade {
+            gives: trade::TradeOffer { cash: 40, ..Default::default() },
+            receives: trade::TradeOffer { streets: vec![1], ..Default::default() }
+        });
+        let counter = trade.counter(trade::TradeOffer { cash: 40, ..Default::default() },
+                                    trade::TradeOffer { streets: vec![1], ..Default::default() });
+        assert_eq!(events, vec![GameEvent::TradeProposed { trade: counter.clone() }]);
+        assert_eq!(g.trade(), Some(&counter));
+
+        let events = g.apply(GameCommand::RejectTrade);
+        assert_eq!(events, vec![GameEvent::TradeRejected { player: 0 }]);
+        assert_eq!(g.trade(), None);
+        assert_eq!(g.board[1].asset().owner(), Some(0));
     }
 
     #[test]
     fn check_house_eligibility() {
         // buy all possible houses for suburb
-        let mut g = init(vec!["Tycoon".to_string()]);
-        g.set_unit_test();
+        let mut g = game(vec!["Tycoon".to_string()], GameMode::Classic, rules::Rules::official());
         let street_idx: usize = 1;
-        let s = g.board.get(street_idx).unwrap();
-        assert_eq!(s.asset.borrow().owner(), None);
+        assert_eq!(g.board[street_idx].asset().owner(), None);
         
-        g.execute_turn(Dice::new(1, 0)); // buys mediterranean
+        move_and_buy(&mut g, Dice::new(1, 0)); // buys mediterranean
         {
-            let owner = g.players.get(0).unwrap().borrow();
-            assert_eq!(g.player_owns_suburb(owner.turn_idx(), &s), false);
+            let s = &g.board[street_idx];
+            assert_eq!(g.player_owns_suburb(0, &s), false);
             assert_eq!(g.street_eligible_for_house(&s), true);
             assert_eq!(g.street_eligible_for_hotel(&s), false);
-            let s = g.board.get(3).unwrap();
-            assert_eq!(g.player_owns_suburb(owner.turn_idx(), &s), false);
+            let s = &g.board[3];
+            assert_eq!(g.player_owns_suburb(0, &s), false);
             assert_eq!(g.street_eligible_for_house(&s), true);
             assert_eq!(g.street_eligible_for_hotel(&s), false);
         }
 
-        g.execute_turn(Dice::new(2, 0)); // buys Baltic
+        move_and_buy(&mut g, Dice::new(2, 0)); // buys Baltic
         {
-            let owner = g.players.get(0).unwrap().borrow();
-            assert_eq!(g.player_owns_suburb(owner.turn_idx(), &s), true);
+            let s = &g.board[3];
+            assert_eq!(g.player_owns_suburb(0, &s), true);
             assert_eq!(g.street_eligible_for_house(&s), true); // can now have houses
             assert_eq!(g.street_eligible_for_hotel(&s), false);
-            let s = g.board.get(1).unwrap();
-            assert_eq!(g.player_owns_suburb(owner.turn_idx(), &s), true);
+            let s = &g.board[1];
+            assert_eq!(g.player_owns_suburb(0, &s), true);
             assert_eq!(g.street_eligible_for_house(&s), true);
             assert_eq!(g.street_eligible_for_hotel(&s), false);
         }
 
         // buy house on mediterranean
         let street_idx = 1;
-        let s = g.board.get(street_idx).unwrap();
-        let mut owner = g.players.get(0).unwrap().borrow_mut();
-        actions::buy_house(&g, &mut owner, street_idx);
-        assert_eq!(g.street_eligible_for_house(&s), false); // cannot buy 2nd house
+        assert_eq!(actions::buy_house(&mut g, 0, street_idx), Ok(()));
+        assert_eq!(g.street_eligible_for_house(&g.board[street_idx]), false); // cannot buy 2nd house
         
         // buy house on baltic
         let street_idx = 3;
-        actions::buy_house(&g, &mut owner, street_idx);
+        assert_eq!(actions::buy_house(&mut g, 0, street_idx), Ok(()));
         
         // buy second house on mediterranean
         let street_idx = 1;
-        let s = g.board.get(street_idx).unwrap();
-        assert_eq!(g.street_eligible_for_house(&s), true); // now eligible
-        actions::buy_house(&g, &mut owner, street_idx);
-        assert_eq!(g.street_eligible_for_house_sale(&s), true);
+        assert_eq!(g.street_eligible_for_house(&g.board[street_idx]), true); // now eligible
+        assert_eq!(actions::buy_house(&mut g, 0, street_idx), Ok(()));
+        assert_eq!(g.street_eligible_for_house_sale(&g.board[street_idx]), true);
 
         // cannot sell house on baltic
         let street_idx = 3;
-        let s = g.board.get(street_idx).unwrap();
-        assert_eq!(g.street_eligible_for_house_sale(&s), false);
+        assert_eq!(g.street_eligible_for_house_sale(&g.board[street_idx]), false);
     }
 
     #[test]
@@ -2167,255 +3392,780 @@ mod tests {
     fn player_in_trouble() {
         // player is in trouble when can't pay their bill
         // move player to income tax 8 times. on the last time, they're in trouble
-        let mut g = init(vec!["Chancer".to_string()]);
-        g.set_unit_test();
-        g.execute_turn(Dice::new(4, 0)); // income tax pay 200
+        let mut g = game(vec!["Chancer".to_string()], GameMode::Classic, rules::Rules::official());
+        move_and_buy(&mut g, Dice::new(4, 0)); // income tax pay 200
         for i in 1..8 {
             {
-                let player = g.players.get(0).unwrap().borrow();
+                let player = &g.players[0];
                 assert_eq!(player.cash(), (1500 - (200*i)));
             }
-            g.execute_turn(Dice::new(40, 0)); // income tax, pay 200
+            move_and_buy(&mut g, Dice::new(40, 0)); // income tax, pay 200
         }
 
-        let player = g.players.get(0).unwrap().borrow();
-        assert_eq!(player.cash(), 100); // didn't pay yet
+        let player = &g.players[0];
+        assert_eq!(player.cash(), 0); // paid what they could
         assert_eq!(player.is_in_trouble(), true); // now in trouble
+        assert_eq!(player.debts(), &[player::Debt {
+            amount: 100, creditor: player::Creditor::Bank,
+            reason: player::DebtReason::Tax { square: 4 } }]);
     }
 
     #[test]
     fn player_on_mortgaged_property() {
-        let mut g = init(vec!["A".to_string(), "B".to_string()]);
-        g.set_unit_test();
+        let mut g = game(vec!["A".to_string(), "B".to_string()], GameMode::Classic,
+                         rules::Rules::official());
         let street_idx = 3;
-        let street = g.board.get(street_idx).unwrap();
 
-        {
-            g.execute_turn(Dice::new(1, 2)); // buy baltic
-            assert_eq!(street.asset.borrow().owner().unwrap(), 0);
-            // mortgage Baltic
-            let mut player = g.players.get(0).unwrap().borrow_mut();
-            actions::mortgage_street(&g, &mut player, street_idx);
-            assert_eq!(street.asset.borrow().is_mortgaged(), true);
-        }
+        move_and_buy(&mut g, Dice::new(1, 2)); // buy baltic
+        assert_eq!(g.board[street_idx].asset().owner().unwrap(), 0);
+        // mortgage Baltic
+        assert_eq!(actions::mortgage_street(&mut g, 0, street_idx), Ok(()));
+        assert_eq!(g.board[street_idx].asset().is_mortgaged(), true);
 
         // Player B lands on baltic and pays no rent
         g.set_active_player(1);
-        g.execute_turn(Dice::new(1, 2)); // land on baltic
-        let player = g.players.get(1).unwrap().borrow();
-        assert_eq!(street.asset.borrow().owner().unwrap(), 0);
-        assert_eq!(player.cash(), 1500);
+        move_and_buy(&mut g, Dice::new(1, 2)); // land on baltic
+        assert_eq!(g.board[street_idx].asset().owner().unwrap(), 0);
+        assert_eq!(g.players[1].cash(), 1500);
     }
 
     #[test]
     fn execute_card_movement() {
-        let mut g = init(vec!["A".to_string()]);
-        g.set_unit_test();
-        g.execute_turn(Dice::new(1, 2));
+        let mut g = game(vec!["A".to_string()], GameMode::Classic, rules::Rules::official());
+        move_and_buy(&mut g, Dice::new(1, 2));
         {
-            let player = g.players.get(0).unwrap().borrow();
+            let player = &g.players[0];
             assert_eq!(player.position(), 3);
         }
         g.execute_card(&card::Card::new("test", card::CardAction::Movement,
-                                        None, Some(10)));
+                                        None, Some(10)), &Dice::new(0, 0));
         {
-            let player = g.players.get(0).unwrap().borrow();
+            let player = &g.players[0];
             assert_eq!(player.position(), 10);
             assert_eq!(player.cash(), 1440);
         }
 
         // advance to GO, only get $200
         g.execute_card(&card::Card::new("test", card::CardAction::Movement,
-                                        None, Some(0)));
-        let player = g.players.get(0).unwrap().borrow();
+                                        None, Some(0)), &Dice::new(0, 0));
+        let player = &g.players[0];
         assert_eq!(player.position(), 0);
         assert_eq!(player.cash(), 1640);
     }
 
     #[test]
     fn execute_card_relative_movement() {
-        let mut g = init(vec!["A".to_string()]);
-        g.set_unit_test();
-        g.execute_turn(Dice::new(1, 2));
+        let mut g = game(vec!["A".to_string()], GameMode::Classic, rules::Rules::official());
+        move_and_buy(&mut g, Dice::new(1, 2));
         {
-            let player = g.players.get(0).unwrap().borrow();
+            let player = &g.players[0];
             assert_eq!(player.position(), 3);
         }
         g.execute_card(&card::Card::new("test", card::CardAction::RelativeMovement,
-                                        None, Some(3)));
+                                        Some(3), None), &Dice::new(0, 0));
         {
-            let player = g.players.get(0).unwrap().borrow();
+            let player = &g.players[0];
             assert_eq!(player.position(), 6);
         }
 
+        // going back past GO doesn't pay the salary
+        let cash = g.players[0].cash();
         g.execute_card(&card::Card::new("test", card::CardAction::RelativeMovement,
-                                        None, Some(36)));
-        let player = g.players.get(0).unwrap().borrow();
-        assert_eq!(player.position(), 2);
+                                        Some(-8), None), &Dice::new(0, 0));
+        let player = &g.players[0];
+        assert_eq!(player.position(), 38);
+        assert_eq!(player.cash(), cash - 100); // Luxury Tax
+    }
+
+    #[test]
+    fn play_on_a_small_board() {
+        let mut edition = edition::Edition::us();
+        edition.squares = [0, 1, 3, 4, 10, 30].iter()
+            .map(|&i| edition.squares[i].clone())
+            .collect();
+        edition.suburbs.truncate(1);
+        assert_eq!(edition.validate(), Ok(()));
+        let mut decks = card::Decks::classic();
+        assert!(init_with_edition(vec!["A".to_string(), "B".to_string()], GameMode::Classic,
+                                  rules::Rules::official(), 1, edition.clone(), decks.clone())
+                .is_err());
+        for cards in [&mut decks.chance, &mut decks.community_chest].iter_mut() {
+            cards.retain(|c| c.square().unwrap_or(0) < 6);
+        }
+        let mut g = init_with_edition(vec!["A".to_string(), "B".to_string()], GameMode::Classic,
+                                      rules::Rules::official(), 1, edition, decks).unwrap();
+
+        g.apply(GameCommand::RollDice(1, 2)); // Income Tax
+        assert_eq!(g.players[0].cash(), 1300);
+        g.apply(GameCommand::EndTurn);
+        g.apply(GameCommand::RollDice(2, 3)); // Go To Jail
+        assert_eq!(g.players[1].position(), 4);
+        assert!(g.players[1].is_in_jail());
+        g.apply(GameCommand::EndTurn);
+        g.apply(GameCommand::RollDice(1, 2)); // around the board, back on GO
+        assert_eq!(g.players[0].position(), 0);
+        assert_eq!(g.players[0].cash(), 1500);
+    }
+
+    #[test]
+    fn execute_card_move_to_nearest() {
+        let mut g = game(vec!["A".to_string(), "B".to_string()], GameMode::Classic,
+                         rules::Rules::official());
+        let station = card::Card::new("test",
+            card::CardAction::MoveToNearest(square::SquareType::Station), Some(2), None);
+        let utility = card::Card::new("test",
+            card::CardAction::MoveToNearest(square::SquareType::Utility), Some(10), None);
+        g.board[15].asset_mut().set_owner(1);
+        g.board[28].asset_mut().set_owner(1);
+
+        g.players[0].advance(7, 40);
+        g.execute_card(&station, &Dice::new(3, 4));
+        assert_eq!(g.players[0].position(), 15);
+        assert_eq!(g.players[1].cash(), 1550); // twice the rent of one station
+
+        // the player rolls again for the rent of the utility
+        g.players[0].advance(7, 40);
+        g.execute_card(&utility, &Dice::new(3, 4));
+        assert_eq!(g.players[0].position(), 28);
+        assert_eq!(g.players[1].cash(), 1550);
+        assert_eq!(g.events.last(), Some(&GameEvent::RollForRent {
+            player: 0, owner: 1, square: 28, factor: 10 }));
+        g.events.clear();
+        g.set_phase(TurnPhase::Actions);
+        assert_eq!(g.apply(GameCommand::EndTurn),
+                   vec![GameEvent::Rejected { error: GameError::RentRollPending }]);
+        assert_eq!(g.apply(GameCommand::RollDice(1, 2)), vec![
+            GameEvent::PaidRent { player: 0, owner: 1, square: 28, amount: 30 }]);
+        assert_eq!(g.players[1].cash(), 1580); // ten times the second roll
+
+        // the nearest station can be past GO, and can be unowned
+        g.players[0].advance(8, 40);
+        g.execute_card(&station, &Dice::new(3, 4));
+        assert_eq!(g.players[0].position(), 5);
+        assert_eq!(g.players[0].cash(), 1500 - 50 - 30 + 200);
+        assert_eq!(g.phase(), TurnPhase::Purchase(5));
     }
 
     #[test]
     fn execute_card_payment() {
-        let mut g = init(vec!["A".to_string()]);
-        g.set_unit_test();
-        g.execute_turn(Dice::new(1, 3));
+        let mut g = game(vec!["A".to_string()], GameMode::Classic, rules::Rules::official());
+        move_and_buy(&mut g, Dice::new(1, 3));
         {
-            let player = g.players.get(0).unwrap().borrow();
+            let player = &g.players[0];
             assert_eq!(player.cash(), 1300);
         }
         g.execute_card(&card::Card::new("test", card::CardAction::Payment,
-                                        Some(100), None));
+                                        Some(100), None), &Dice::new(0, 0));
         {
-            let player = g.players.get(0).unwrap().borrow();
+            let player = &g.players[0];
             assert_eq!(player.cash(), 1200);
         }
 
         g.execute_card(&card::Card::new("test", card::CardAction::Payment,
-                                        Some(-50), None));
-        let player = g.players.get(0).unwrap().borrow();
+                                        Some(-50), None), &Dice::new(0, 0));
+        let player = &g.players[0];
         assert_eq!(player.cash(), 1250);
     }
 
     #[test]
     fn execute_card_jail() {
-        let mut g = init(vec!["A".to_string()]);
-        g.set_unit_test();
-        g.execute_turn(Dice::new(1, 3));
+        let mut g = game(vec!["A".to_string()], GameMode::Classic, rules::Rules::official());
+        move_and_buy(&mut g, Dice::new(1, 3));
         {
-            let player = g.players.get(0).unwrap().borrow();
+            let player = &g.players[0];
             assert_eq!(player.cash(), 1300);
         }
         g.execute_card(&card::Card::new("test", card::CardAction::Jail,
-                                        None, None));
+                                        None, None), &Dice::new(0, 0));
         {
-            let player = g.players.get(0).unwrap().borrow();
+            let player = &g.players[0];
             assert_eq!(player.is_in_jail(), true);
             assert_eq!(player.num_get_out_of_jail_cards(), 0);
         }
 
-        g.execute_card(&card::Card::new("test", card::CardAction::JailRelease,
-                                        None, None));
-        let player = g.players.get(0).unwrap().borrow();
+        g.chance_cards.insert(0, jail_card());
+        let cards = g.chance_cards.len();
+        g.draw_card(card::Deck::Chance, &Dice::new(0, 0));
+        let player = &g.players[0];
         assert_eq!(player.is_in_jail(), true);
         assert_eq!(player.num_get_out_of_jail_cards(), 1);
+        assert_eq!(g.chance_cards.len(), cards - 1); // the player holds the card
+
+        // the card goes back to the bottom of its deck
+        g.apply(GameCommand::UseJailCard);
+        assert_eq!(g.players[0].is_in_jail(), false);
+        assert_eq!(g.chance_cards.len(), cards);
+        assert_eq!(g.chance_cards.last(), Some(&jail_card()));
+
+        // nothing happens when the players hold every card of the deck
+        g.chance_cards = vec![jail_card()];
+        g.draw_card(card::Deck::Chance, &Dice::new(0, 0));
+        g.events.clear();
+        g.draw_card(card::Deck::Chance, &Dice::new(0, 0));
+        assert_eq!(g.events, vec![GameEvent::DeckEmpty { player: 0, square: 10 }]);
+        assert_eq!(g.players[0].num_get_out_of_jail_cards(), 1);
+    }
+
+    #[test]
+    fn jail_cards_of_leaving_player_go_back_to_their_decks() {
+        let mut g = game(vec!["A".to_string(), "B".to_string(), "C".to_string()],
+                         GameMode::Classic, rules::Rules::official());
+        g.community_cards.retain(|c| *c.action() != card::CardAction::JailRelease);
+        let cards = g.community_cards.len();
+        g.players[0].receive_jail_free_card(card::Deck::CommunityChest, jail_card());
+        g.apply(GameCommand::LeaveGame);
+        assert_eq!(g.community_cards.len(), cards + 1);
+        assert_eq!(g.community_cards.last(), Some(&jail_card()));
+    }
+
+    #[test]
+    fn execute_card_collect_from_players() {
+        let mut g = game(vec!["A".to_string(), "B".to_string(), "C".to_string()],
+                         GameMode::Classic, rules::Rules::official());
+        g.players[2].transact_cash(-1495).unwrap();
+        g.execute_card(&card::Card::new("test", card::CardAction::CollectFromPlayers,
+                                        Some(10), None), &Dice::new(0, 0));
+        assert_eq!(g.players[0].cash(), 1515);
+        assert_eq!(g.players[1].cash(), 1490);
+        assert_eq!(g.players[2].cash(), 0);
+        let debt = player::Debt { amount: 5, creditor: player::Creditor::Player(0),
+                                  reason: player::DebtReason::Card };
+        assert_eq!(g.players[2].debts(), &[debt]);
+        assert!(g.events.contains(&GameEvent::PaidPlayer { player: 2, to: 0, amount: 5 }));
+        assert!(g.events.contains(&GameEvent::BecameInTrouble { player: 2, debt }));
+    }
+
+    #[test]
+    fn broke_player_settles_outside_their_turn() {
+        let mut g = game(vec!["A".to_string(), "B".to_string(), "C".to_string()],
+                         GameMode::Classic, rules::Rules::official());
+        g.players[1].transact_cash(-1480).unwrap();
+        g.board[1].asset_mut().set_owner(1);
+        g.players[2].transact_cash(-1470).unwrap();
+        g.set_phase(TurnPhase::Actions);
+        g.execute_card(&card::Card::new("test", card::CardAction::CollectFromPlayers,
+                                        Some(50), None), &Dice::new(0, 0));
+        g.events.clear();
+        assert_eq!(g.apply(GameCommand::EndTurn),
+                   vec![GameEvent::Rejected { error: GameError::PlayerInTrouble { player: 1 } }]);
+        assert_eq!(g.apply(GameCommand::GoBankrupt { player: 0 }),
+                   vec![GameEvent::Rejected { error: GameError::NotInTrouble }]);
+
+        // B mortgages their street to pay the rest
+        let events = g.apply(GameCommand::Mortgage { street: 1 });
+        assert!(events.contains(&GameEvent::OutOfTrouble { player: 1 }));
+        assert_eq!(g.players[1].cash(), 0);
+
+        // C can't pay, and hands everything over to A
+        assert_eq!(g.apply(GameCommand::EndTurn),
+                   vec![GameEvent::Rejected { error: GameError::PlayerInTrouble { player: 2 } }]);
+        let events = g.apply(GameCommand::GoBankrupt { player: 2 });
+        assert_eq!(events[0], GameEvent::WentBankrupt {
+            player: 2, creditor: player::Creditor::Player(0) });
+        assert!(events.contains(&GameEvent::LeftGame { player: 2 }));
+        assert_eq!(g.players[0].cash(), 1500 + 50 + 30); // C only had $30
+        assert_eq!(g.active_player(), 0);
+        assert_eq!(g.phase(), TurnPhase::Actions);
+        g.apply(GameCommand::EndTurn);
+        assert_eq!(g.active_player(), 1);
+    }
+
+    #[test]
+    fn debts_to_leaving_player_pass_to_their_creditor() {
+        let mut g = game(vec!["A".to_string(), "B".to_string(), "C".to_string()],
+                         GameMode::Classic, rules::Rules::official());
+        g.players[1].transact_cash(-1470).unwrap();
+        g.players[2].transact_cash(-1500).unwrap();
+        g.set_phase(TurnPhase::Actions);
+        g.execute_card(&card::Card::new("test", card::CardAction::CollectFromPlayers,
+                                        Some(50), None), &Dice::new(0, 0));
+        g.players[0].owe(player::Debt { amount: 5000, creditor: player::Creditor::Player(2),
+                                        reason: player::DebtReason::Rent { square: 39 } });
+
+        // C no longer owes themselves, B now owes C
+        let events = g.apply(GameCommand::LeaveGame);
+        assert!(events.contains(&GameEvent::OutOfTrouble { player: 2 }));
+        assert_eq!(g.players[2].cash(), 1500 + 30);
+        assert_eq!(g.players[1].debts(), &[player::Debt {
+            amount: 20, creditor: player::Creditor::Player(2), reason: player::DebtReason::Card }]);
+        assert_eq!(g.players[2].debts(), &[]);
+    }
+
+    #[test]
+    fn execute_card_pay_players() {
+        let mut g = game(vec!["A".to_string(), "B".to_string(), "C".to_string()],
+                         GameMode::Classic, rules::Rules::official());
+        g.players[0].transact_cash(-1440).unwrap();
+        g.execute_card(&card::Card::new("test", card::CardAction::PayPlayers,
+                                        Some(50), None), &Dice::new(0, 0));
+        assert_eq!(g.players[0].cash(), 0);
+        assert_eq!(g.players[1].cash(), 1550);
+        assert_eq!(g.players[2].cash(), 1510);
+        assert_eq!(g.players[0].debts(), &[player::Debt {
+            amount: 40, creditor: player::Creditor::Player(2),
+            reason: player::DebtReason::Card }]);
     }
 
     #[test]
     fn execute_card_repairs() {
-        let mut g = init(vec!["A".to_string()]);
-        g.set_unit_test();
+        let mut g = game(vec!["A".to_string()], GameMode::Classic, rules::Rules::official());
 
         // buy indigo squares
-        g.execute_turn(Dice::new(1, 0));
-        g.execute_turn(Dice::new(2, 0));
+        move_and_buy(&mut g, Dice::new(1, 0));
+        move_and_buy(&mut g, Dice::new(2, 0));
 
         // put houses on all squares
-        {
-            let mut owner = g.players.get(0).unwrap().borrow_mut();
-            for _ in 0..4 {
-                // buy another house in Mediterranean then Baltic
-                let street_idx = 1;
-                actions::buy_house(&g, &mut owner, street_idx);
-
-                let street_idx = 3;
-                actions::buy_house(&g, &mut owner, street_idx);
-            }
+        for _ in 0..4 {
+            // buy another house in Mediterranean then Baltic
+            let street_idx = 1;
+            assert_eq!(actions::buy_house(&mut g, 0, street_idx), Ok(()));
+
+            let street_idx = 3;
+            assert_eq!(actions::buy_house(&mut g, 0, street_idx), Ok(()));
         }
 
         // double check, both streets should have 4 houses
-        assert_eq!(g.board.get(1).unwrap().asset.borrow().house_num(), 4);
-        assert_eq!(g.board.get(3).unwrap().asset.borrow().house_num(), 4);
+        assert_eq!(g.board[1].asset().house_num(), 4);
+        assert_eq!(g.board[3].asset().house_num(), 4);
 
         // buying hotels succeeds
         let street_idx = 1;
-        let s = g.board.get(street_idx).unwrap();
-        {
-            let mut owner = g.players.get(0).unwrap().borrow_mut();
-            actions::buy_hotel(&g, &mut owner, street_idx);
-            assert_eq!(s.asset.borrow().has_hotel(), true);
-            assert_eq!(owner.cash(), 930);
-        }
+        assert_eq!(actions::buy_hotel(&mut g, 0, street_idx), Ok(()));
+        assert_eq!(g.board[street_idx].asset().has_hotel(), true);
+        assert_eq!(g.players[0].cash(), 930);
 
 
         // now calculate repairs bill for 4 houses and 1 hotel
         g.execute_card(&card::Card::new("test", card::CardAction::Repairs,
-                                        Some(2), Some(100))); // total 108
-        {
-            let player = g.players.get(0).unwrap().borrow();
-            assert_eq!(player.cash(), 822);
-        }
+                                        Some(2), Some(100)), &Dice::new(0, 0)); // total 108
+        assert_eq!(g.players[0].cash(), 822);
 
         g.execute_card(&card::Card::new("test", card::CardAction::Repairs,
-                                        Some(100), Some(3))); // total 403
-        let player = g.players.get(0).unwrap().borrow();
-        assert_eq!(player.cash(), 419);
+                                        Some(100), Some(3)), &Dice::new(0, 0)); // total 403
+        assert_eq!(g.players[0].cash(), 419);
     }
 
     #[test]
     fn sell_house_before_street() {
-        let mut g = init(vec!["Seller".to_string(), "Owner".to_string()]);
-        g.set_unit_test();
+        let mut g = game(vec!["Seller".to_string(), "Owner".to_string()], GameMode::Classic,
+                         rules::Rules::official());
         let street_idx: usize = 1;
-        let s = g.board.get(street_idx).unwrap();
 
         // buy brown squares
-        g.execute_turn(Dice::new(1, 0));
-        g.execute_turn(Dice::new(2, 0));
+        move_and_buy(&mut g, Dice::new(1, 0));
+        move_and_buy(&mut g, Dice::new(2, 0));
 
         //buy house
-        let mut seller = g.players.get(0).unwrap().borrow_mut();
-        let street_idx = 1;
-        actions::buy_house(&g, &mut seller, street_idx);
+        assert_eq!(actions::buy_house(&mut g, 0, street_idx), Ok(()));
 
         // selling street fails
-        let mut owner = g.players.get(1).unwrap().borrow_mut();
-        let response = g.sell_property(&mut seller, &mut owner, &s, 20);
-        assert_eq!(response, Err(()));
+        let response = g.sell_property(0, 1, street_idx, 20);
+        assert_eq!(response,
+                   Err(GameError::HasBuildings));
+    }
+
+    #[test]
+    fn apply_commands_for_a_turn() {
+        let mut g = game(vec!["A".to_string(), "B".to_string()], GameMode::Classic,
+                         rules::Rules::official());
+        let events = g.apply(GameCommand::RollDice(1, 2));
+        assert_eq!(events, vec![
+            GameEvent::Moved { player: 0, from: 0, to: 3 },
+            GameEvent::PurchaseOffered { player: 0, square: 3, price: 60 }
+        ]);
+        assert_eq!(g.phase(), TurnPhase::Purchase(3));
+
+        // cannot end the turn before deciding on the street
+        let events = g.apply(GameCommand::EndTurn);
+        assert_eq!(events.len(), 1);
+        assert_eq!(g.active_player(), 0);
+
+        let events = g.apply(GameCommand::BuyProperty);
+        assert_eq!(events, vec![
+            GameEvent::BoughtProperty { player: 0, square: 3, price: 60 }]);
+        assert_eq!(g.phase(), TurnPhase::Actions);
+
+        let events = g.apply(GameCommand::EndTurn);
+        assert_eq!(events, vec![GameEvent::TurnStarted { player: 1 }]);
+        assert_eq!(g.phase(), TurnPhase::Roll);
+    }
+
+    #[test]
+    fn apply_double_then_auction() {
+        let mut g = game(vec!["A".to_string(), "B".to_string()], GameMode::Classic,
+                         rules::Rules::official());
+        let events = g.apply(GameCommand::RollDice(2, 2));
+        assert_eq!(events, vec![GameEvent::RolledDouble { player: 0 }]);
+        assert_eq!(g.phase(), TurnPhase::Roll);
+
+        // move the sum of both rolls, to St. Charles Place
+        g.apply(GameCommand::RollDice(3, 4));
+        assert_eq!(g.players[0].position(), 11);
+        assert_eq!(g.phase(), TurnPhase::Purchase(11));
+
+        let events = g.apply(GameCommand::DeclinePurchase);
+        assert_eq!(events, vec![GameEvent::AuctionStarted { square: 11 }]);
+        g.apply(GameCommand::PassBid);
+        g.apply(GameCommand::Bid { amount: 90 });
+        assert_eq!(g.board[11].asset().owner(), Some(1));
+        assert_eq!(g.players[1].cash(), 1410);
+        assert_eq!(g.phase(), TurnPhase::Actions);
+    }
+
+    #[test]
+    fn apply_bids_until_all_others_pass() {
+        let mut g = game(vec!["A".to_string(), "B".to_string(), "C".to_string()],
+                         GameMode::Classic, rules::Rules::official());
+        g.apply(GameCommand::RollDice(1, 2)); // Baltic Avenue
+        g.apply(GameCommand::DeclinePurchase);
+        assert_eq!(g.auction().unwrap().bidders(), &[0, 1, 2]);
+
+        let rejected = |error| vec![GameEvent::Rejected { error }];
+        assert_eq!(g.apply(GameCommand::Bid { amount: 5 }),
+                   rejected(GameError::BidTooLow { minimum: 10 }));
+        assert_eq!(g.apply(GameCommand::Bid { amount: 10 }),
+                   vec![GameEvent::BidPlaced { player: 0, amount: 10 }]);
+        assert_eq!(g.apply(GameCommand::Bid { amount: 1600 }),
+                   rejected(GameError::InsufficientFunds { needed: 1600, available: 1500 }));
+        assert_eq!(g.apply(GameCommand::Bid { amount: 30 }),
+                   vec![GameEvent::BidPlaced { player: 1, amount: 30 }]);
+        assert_eq!(g.apply(GameCommand::PassBid), vec![GameEvent::PassedBid { player: 2 }]);
+        assert_eq!(g.auction().unwrap().bidder(), Some(0));
+        assert_eq!(g.apply(GameCommand::PassBid), vec![
+            GameEvent::PassedBid { player: 0 },
+            GameEvent::BoughtProperty { player: 1, square: 3, price: 30 }
+        ]);
+        assert_eq!(g.board[3].asset().owner(), Some(1));
+        assert_eq!(g.players[1].cash(), 1470);
+        assert_eq!(g.phase(), TurnPhase::Actions);
+        assert_eq!(g.auction(), None);
+    }
+
+    #[test]
+    fn apply_auction_without_bids() {
+        let mut g = game(vec!["A".to_string(), "B".to_string()], GameMode::Classic,
+                         rules::Rules::official());
+        g.apply(GameCommand::RollDice(1, 2)); // Baltic Avenue
+        g.apply(GameCommand::DeclinePurchase);
+        g.apply(GameCommand::PassBid);
+        let events = g.apply(GameCommand::PassBid);
+        assert_eq!(events, vec![
+            GameEvent::PassedBid { player: 1 },
+            GameEvent::NoBids { square: 3 }
+        ]);
+        assert_eq!(g.board[3].asset().owner(), None);
+        assert_eq!(g.phase(), TurnPhase::Actions);
+        assert_eq!(g.apply(GameCommand::PassBid),
+                   vec![GameEvent::Rejected { error: GameError::NoAuction }]);
+    }
+
+    #[test]
+    fn apply_rejects_invalid_commands() {
+        let mut g = game(vec!["A".to_string(), "B".to_string()], GameMode::Classic,
+                         rules::Rules::official());
+        assert_eq!(g.apply(GameCommand::EndTurn),
+                   vec![GameEvent::Rejected { error: GameError::DiceNotRolled }]);
+        assert_eq!(g.apply(GameCommand::RollDice(7, 1)),
+                   vec![GameEvent::Rejected { error: GameError::InvalidDice }]);
+        assert_eq!(g.apply(GameCommand::Mortgage { street: 3 }),
+                   vec![GameEvent::Rejected { error: GameError::Unowned }]);
+        assert_eq!(g.phase(), TurnPhase::Roll);
+    }
+
+    #[test]
+    fn actions_return_precise_errors() {
+        let mut g = game(vec!["A".to_string(), "B".to_string()], GameMode::Classic,
+                         rules::Rules::official());
+        move_and_buy(&mut g, Dice::new(1, 0)); // Buy Mediterranean
+        assert_eq!(actions::buy_house(&mut g, 1, 1), Err(GameError::NotOwner));
+        assert_eq!(actions::buy_house(&mut g, 0, 1), Err(GameError::SuburbIncomplete));
+        assert_eq!(actions::buy_house(&mut g, 0, 5), Err(GameError::NotBuildable));
+        assert_eq!(actions::unmortgage_street(&mut g, 0, 1), Err(GameError::NotMortgaged));
+
+        assert_eq!(actions::mortgage_street(&mut g, 0, 1), Ok(()));
+        assert_eq!(actions::mortgage_street(&mut g, 0, 1), Err(GameError::AlreadyMortgaged));
+        let cash = g.players[1].cash();
+        // the buyer also pays 10% interest on the mortgage of $30
+        assert_eq!(g.sell_property(0, 1, 1, cash + 1),
+                   Err(GameError::InsufficientFunds { needed: cash + 4, available: cash }));
+    }
+
+    #[test]
+    fn buyer_of_mortgaged_street_chooses_to_lift_mortgage() {
+        let mut g = game(vec!["A".to_string(), "B".to_string()], GameMode::Classic,
+                         rules::Rules::official());
+        build_streets(&mut g, 0, &[1, 3], 0);
+        g.board[1].asset_mut().mortgage();
+        g.board[3].asset_mut().mortgage();
+        g.apply(GameCommand::RollDice(2, 3)); // Reading Railroad
+        g.apply(GameCommand::BuyProperty);
+
+        let events = g.apply(GameCommand::SellStreet { street: 3, buyer: 1, price: 100 });
+        assert_eq!(events, vec![
+            GameEvent::SoldStreet { seller: 0, buyer: 1, square: 3, price: 100 },
+            GameEvent::PaidInterest { player: 1, square: 3, amount: 3 }
+        ]);
+        assert_eq!(g.mortgage_choices(), &[3]);
+        assert_eq!(g.apply(GameCommand::EndTurn),
+                   vec![GameEvent::Rejected { error: GameError::MortgageChoicePending }]);
+
+        // lifting the mortgage straight away costs the mortgage, without interest
+        let events = g.apply(GameCommand::Unmortgage { street: 3 });
+        assert_eq!(events, vec![GameEvent::Unmortgaged { player: 1, square: 3, amount: 30 }]);
+        assert_eq!(g.players[1].cash(), 1500 - 100 - 3 - 30);
+
+        // keeping the mortgage means paying interest again later
+        g.apply(GameCommand::SellStreet { street: 1, buyer: 1, price: 10 });
+        assert_eq!(g.apply(GameCommand::KeepMortgage { street: 1 }),
+                   vec![GameEvent::KeptMortgage { player: 1, square: 1 }]);
+        assert!(g.mortgage_choices().is_empty());
+        assert_eq!(g.apply(GameCommand::KeepMortgage { street: 1 }),
+                   vec![GameEvent::Rejected { error: GameError::NoMortgageChoice }]);
+        let events = g.apply(GameCommand::Unmortgage { street: 1 });
+        assert_eq!(events, vec![GameEvent::Unmortgaged { player: 1, square: 1, amount: 33 }]);
+        assert_eq!(g.apply(GameCommand::EndTurn), vec![GameEvent::TurnStarted { player: 1 }]);
+    }
+
+    #[test]
+    fn bankrupt_player_hands_assets_to_creditor() {
+        let mut g = game(vec!["A".to_string(), "B".to_string(), "C".to_string()], GameMode::Classic,
+                         rules::Rules::official());
+        move_and_buy(&mut g, Dice::new(1, 0)); // Buy Mediterranean
+        move_and_buy(&mut g, Dice::new(2, 0)); // Buy Baltic
+        assert_eq!(actions::buy_house(&mut g, 0, 1), Ok(()));
+        assert_eq!(actions::mortgage_street(&mut g, 0, 3), Ok(()));
+        g.players[0].receive_jail_free_card(card::Deck::Chance, jail_card());
+        let house_price = g.board[1].get_street_details().unwrap()
+            .get_suburb().unwrap().building_price();
+        let cash = g.players[0].cash();
+
+        g.players[0].owe(player::Debt { amount: 5000, creditor: player::Creditor::Player(1),
+                                        reason: player::DebtReason::Rent { square: 39 } });
+        let events = g.apply(GameCommand::LeaveGame);
+        assert!(events.contains(&GameEvent::WentBankrupt {
+            player: 0, creditor: player::Creditor::Player(1) }));
+        assert!(events.contains(&GameEvent::PaidInterest { player: 1, square: 3, amount: 3 }));
+        assert_eq!(g.players[0].cash(), 0);
+        assert_eq!(g.players[1].cash(), 1500 + cash + house_price - 3);
+        assert_eq!(g.board[1].asset().owner(), Some(1));
+        assert_eq!(g.board[1].asset().house_num(), 0);
+        assert_eq!(g.board[3].asset().owner(), Some(1));
+        assert_eq!(g.board[3].asset().is_mortgaged(), true);
+        assert_eq!(g.players[1].num_get_out_of_jail_cards(), 1);
+        assert_eq!(g.phase(), TurnPhase::Roll);
+    }
+
+    #[test]
+    fn bank_auctions_assets_of_leaving_player() {
+        let mut g = game(vec!["A".to_string(), "B".to_string(), "C".to_string()], GameMode::Classic,
+                         rules::Rules::official());
+        move_and_buy(&mut g, Dice::new(1, 0)); // Buy Mediterranean
+        move_and_buy(&mut g, Dice::new(2, 0)); // Buy Baltic
+        assert_eq!(actions::mortgage_street(&mut g, 0, 3), Ok(()));
+
+        let events = g.apply(GameCommand::LeaveGame);
+        assert!(events.contains(&GameEvent::ReturnedToBank { player: 0, square: 3 }));
+        assert_eq!(g.active_player(), 1);
+        assert_eq!(g.phase(), TurnPhase::Auction(1));
+        assert_eq!(g.board[3].asset().is_mortgaged(), false);
+
+        g.apply(GameCommand::Bid { amount: 10 });
+        g.apply(GameCommand::PassBid);
+        assert_eq!(g.board[1].asset().owner(), Some(1));
+        assert_eq!(g.phase(), TurnPhase::Auction(3));
+        g.apply(GameCommand::DeclinePurchase);
+        assert_eq!(g.board[3].asset().owner(), None);
+        assert_eq!(g.phase(), TurnPhase::Roll);
+    }
+
+    #[test]
+    fn bank_auctions_assets_of_player_bankrupt_outside_their_turn() {
+        let mut g = game(vec!["A".to_string(), "B".to_string(), "C".to_string(),
+                              "D".to_string()], GameMode::Classic, rules::Rules::official());
+        g.board[1].asset_mut().set_owner(0);
+        g.board[1].asset_mut().mortgage();
+        g.players[0].transact_cash(-1500).unwrap();
+        g.players[2].transact_cash(-1500).unwrap();
+        g.players[0].owe(player::Debt { amount: 100, creditor: player::Creditor::Player(2),
+                                        reason: player::DebtReason::Card });
+
+        // C takes over the mortgaged street of A, and can't pay the interest
+        g.apply(GameCommand::LeaveGame);
+        assert_eq!(g.board[1].asset().owner(), Some(2));
+        assert!(g.players[2].is_in_trouble());
+        assert_eq!(g.active_player(), 1);
+        assert_eq!(g.phase(), TurnPhase::Roll);
+
+        let events = g.apply(GameCommand::GoBankrupt { player: 2 });
+        assert!(events.contains(&GameEvent::ReturnedToBank { player: 2, square: 1 }));
+        assert_eq!(g.phase(), TurnPhase::Auction(1));
+        g.apply(GameCommand::Bid { amount: 10 });
+        g.apply(GameCommand::PassBid);
+        assert_eq!(g.board[1].asset().owner(), Some(1));
+        assert!(g.auctions.is_empty());
+        assert_eq!(g.active_player(), 1);
+        assert_eq!(g.phase(), TurnPhase::Roll);
+    }
+
+    #[test]
+    fn game_ends_when_one_player_remains() {
+        let mut g = game(vec!["A".to_string(), "B".to_string()], GameMode::Classic,
+                         rules::Rules::official());
+        let events = g.apply(GameCommand::LeaveGame);
+        assert_eq!(events.last(), Some(&GameEvent::GameOver {
+            winner: 1,
+            standings: vec![Standing { player: 1, net_worth: 1500 },
+                            Standing { player: 0, net_worth: 0 }] }));
+        assert_eq!(g.phase(), TurnPhase::GameOver);
+        assert_eq!(g.winner(), Some(1));
+        assert_eq!(g.apply(GameCommand::RollDice(1, 2)),
+                   vec![GameEvent::Rejected { error: GameError::GameOver }]);
+    }
+
+    #[test]
+    fn end_game_ranks_players_by_net_worth() {
+        let mut g = game(vec!["A".to_string(), "B".to_string(), "C".to_string()], GameMode::Classic,
+                         rules::Rules::official());
+        move_and_buy(&mut g, Dice::new(1, 0)); // Buy Mediterranean
+        assert_eq!(actions::mortgage_street(&mut g, 0, 1), Ok(()));
+        assert_eq!(g.net_worth(0), 1500 - 60 + 30 + 30);
+        g.players[2].transact_cash(100).unwrap();
+        assert_eq!(g.winner(), None);
+
+        g.apply(GameCommand::EndGame);
+        assert_eq!(g.winner(), Some(2));
+        assert_eq!(g.standings().iter().map(|s| s.player).collect::<Vec<usize>>(),
+                   vec![2, 0, 1]);
+    }
+
+    #[test]
+    fn turn_limit_ends_game() {
+        let mut g = game(vec!["A".to_string(), "B".to_string()], GameMode::TurnLimit(2),
+                         rules::Rules::official());
+        g.apply(GameCommand::RollDice(4, 6)); // just visiting jail
+        g.apply(GameCommand::EndTurn);
+        assert_eq!(g.turn(), 2);
+        g.apply(GameCommand::RollDice(4, 6));
+        match g.apply(GameCommand::EndTurn).pop() {
+            Some(GameEvent::GameOver { .. }) => {},
+            e => panic!("Expected the game to be over, got {:?}", e)
+        };
+        assert_eq!(g.phase(), TurnPhase::GameOver);
+    }
+
+    #[test]
+    fn time_limit_ends_game() {
+        let mut g = game(vec!["A".to_string(), "B".to_string()], GameMode::TimeLimit(30),
+                         rules::Rules::official());
+        assert!(g.seconds_left().unwrap() > 29 * 60);
+        g.deadline = Some(0);
+        match g.apply(GameCommand::RollDice(4, 6)).pop() {
+            Some(GameEvent::GameOver { .. }) => {},
+            e => panic!("Expected the game to be over, got {:?}", e)
+        };
+        // the end of the game is journaled, so the game can be replayed
+        assert_eq!(g.journal().entries().last().unwrap().command, GameCommand::EndGame);
+        assert_eq!(g.journal().replay().unwrap().phase(), TurnPhase::GameOver);
     }
 
     #[test]
     fn send_to_square_without_money() {
-        let mut g = init(vec!["Foo".to_string(), "Bar".to_string()]);
-        g.set_unit_test();
+        let mut g = game(vec!["Foo".to_string(), "Bar".to_string()], GameMode::Classic,
+                         rules::Rules::official());
         let street_idx: usize = 1;
 
-        g.execute_turn(Dice::new(1, 0)); // Buy Mediterranean
-        g.execute_turn(Dice::new(38, 0)); // Buy boardwalk
+        move_and_buy(&mut g, Dice::new(1, 0)); // Buy Mediterranean
+        move_and_buy(&mut g, Dice::new(38, 0)); // Buy boardwalk
 
         // Bar buy house, to reduce cash
-        {
-            let mut seller = g.players.get(0).unwrap().borrow_mut();
-            let mut buyer = g.players.get(1).unwrap().borrow_mut();
-            let street_idx = 1;
-            actions::sell_street(&g, &mut seller, &mut buyer, street_idx, 1500);
-            assert_eq!(buyer.is_in_trouble(), false);
-            assert_eq!(seller.cash(), 2540);
-        }
+        assert_eq!(actions::sell_street(&mut g, 0, 1, street_idx, 1500), Ok(()));
+        assert_eq!(g.players[1].is_in_trouble(), false);
+        assert_eq!(g.players[0].cash(), 2540);
 
         g.set_active_player(1);
 
         // move to boardwalk, and owe $50 rent
         g.execute_card(&card::Card::new("Draw Card", card::CardAction::Movement,
-                                        None, Some(39))); 
-        {
-            let p = g.players.get(1).unwrap().borrow_mut();
-            assert_eq!(p.is_in_trouble(), true);
-            assert_eq!(p.cash(), 0);
-        }
-        // sell property back for 50 to get out of trouble
-        {
-            let mut seller = g.players.get(1).unwrap().borrow_mut();
-            let mut buyer = g.players.get(0).unwrap().borrow_mut();
-            let street_idx = 1;
-            actions::sell_street(&g, &mut seller, &mut buyer, street_idx, 50);
-            assert_eq!(seller.cash(), 50);
-            assert_eq!(buyer.cash(), 2490);
-        }
-        g.execute_turn(Dice::new(0, 0)); // try get out of trouble
-        let mut seller = g.players.get(1).unwrap().borrow_mut();
-        let mut buyer = g.players.get(0).unwrap().borrow_mut();
-        assert_eq!(buyer.cash(), 2540);
-        assert_eq!(seller.cash(), 0);
-        assert_eq!(seller.is_in_trouble(), false);
+                                        None, Some(39)), &Dice::new(0, 0));
+        assert_eq!(g.players[1].is_in_trouble(), true);
+        assert_eq!(g.players[1].cash(), 0);
+
+        assert_eq!(g.apply(GameCommand::EndTurn).pop(),
+                   Some(GameEvent::Rejected { error: GameError::OutstandingDebt { amount: 50 } }));
+
+        // sell property back for 50 to pay the debt
+        g.apply(GameCommand::SellStreet { street: street_idx, buyer: 0, price: 50 });
+        assert_eq!(g.players[0].cash(), 2540);
+        assert_eq!(g.players[1].cash(), 0);
+        assert_eq!(g.players[1].is_in_trouble(), false);
+    }
+
+    #[test]
+    fn undo_and_redo_commands() {
+        let mut g = game(vec!["A".to_string(), "B".to_string()], GameMode::Classic,
+                         rules::Rules::official());
+        assert_eq!(g.apply(GameCommand::Undo),
+                   vec![GameEvent::Rejected { error: GameError::NothingToUndo }]);
+        let before = g.save();
+
+        g.apply(GameCommand::RollDice(2, 2)); // double
+        g.apply(GameCommand::RollDice(1, 2)); // Chance, cards are drawn
+        let rolled = g.save();
+        assert_eq!(g.apply(GameCommand::Undo),
+                   vec![GameEvent::Undone { command: GameCommand::RollDice(1, 2) }]);
+        assert_eq!(g.players[0].position(), 0);
+        assert_eq!(g.dice.clone(), Some(Dice::new(2, 2)));
+        assert_eq!(g.chance_cards.clone(), before.chance_cards);
+
+        g.apply(GameCommand::Undo);
+        assert_eq!(g.save().players, before.players);
+        assert_eq!(g.phase(), TurnPhase::Roll);
+        assert_eq!(g.dice.clone(), None);
+
+        g.apply(GameCommand::Redo);
+        let events = g.apply(GameCommand::Redo);
+        assert_eq!(events[0], GameEvent::Redone { command: GameCommand::RollDice(1, 2) });
+        assert_eq!(g.save(), rolled);
+        assert_eq!(g.apply(GameCommand::Redo),
+                   vec![GameEvent::Rejected { error: GameError::NothingToRedo }]);
+    }
+
+    #[test]
+    fn undo_turn() {
+        let mut g = game(vec!["A".to_string(), "B".to_string()], GameMode::Classic,
+                         rules::Rules::official());
+        g.apply(GameCommand::RollDice(1, 2)); // Baltic Avenue
+        g.apply(GameCommand::BuyProperty);
+        g.apply(GameCommand::EndTurn);
+        let turn_started = g.save();
+        g.apply(GameCommand::RollDice(2, 3)); // Reading Railroad
+        g.apply(GameCommand::BuyProperty);
+
+        assert_eq!(g.apply(GameCommand::UndoTurn).len(), 2);
+        assert_eq!(g.save().players, turn_started.players);
+        assert_eq!(g.active_player(), 1);
+
+        // at the start of the turn, the previous turn is undone
+        g.apply(GameCommand::UndoTurn);
+        assert_eq!(g.active_player(), 0);
+        assert_eq!(g.journal().entries().len(), 0);
+        assert_eq!(g.board[3].asset().owner(), None);
+        assert_eq!(g.players[0].cash(), 1500);
+
+        // a new command can't be followed by a redo
+        g.apply(GameCommand::RollDice(1, 2));
+        assert_eq!(g.journal().undone().len(), 0);
+    }
+
+    #[test]
+    fn game_moves_between_threads() {
+        let mut g = game(vec!["A".to_string(), "B".to_string()], GameMode::Classic,
+                         rules::Rules::official());
+        let handle = std::thread::spawn(move || {
+            g.apply(GameCommand::RollDice(1, 2));
+            g
+        });
+        let g = handle.join().unwrap();
+        assert_eq!(g.players[0].position(), 3);
     }
 }
@@ -0,0 +1,181 @@
+use serde::{Deserialize, Serialize};
+
+use super::card::Decks;
+use super::command::GameCommand;
+use super::edition::Edition;
+use super::error::GameError;
+use super::event::GameEvent;
+use super::game::{self, Game, GameMode};
+use super::rules::Rules;
+
+/// A command executed by the game, and everything that happened as a result
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub struct JournalEntry {
+    pub command: GameCommand,
+    pub events: Vec<GameEvent>
+}
+
+/// Record of everything that happened in a game
+// Entries are only ever appended. Since the seed determines the order of the cards,
+// and the dice rolls are part of the commands, replaying the commands on a new game
+// with the same seed and players reproduces the game exactly.
+// Undoing a command moves its entry to the undone entries, from where it can be redone.
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub struct Journal {
+    seed: u64,
+    mode: GameMode,
+    rules: Rules,
+    players: Vec<String>,
+    edition: Edition,
+    decks: Decks, // before shuffling
+    entries: Vec<JournalEntry>,
+    undone: Vec<JournalEntry> // the most recently undone entry is last
+}
+
+impl Journal {
+    pub fn new(seed: u64, mode: GameMode, rules: Rules, players: Vec<String>,
+               edition: Edition, decks: Decks) -> Self {
+        Self {
+            seed,
+            mode,
+            rules,
+            players,
+            edition,
+            decks,
+            entries: Vec::new(),
+            undone: Vec::new()
+        }
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    pub fn mode(&self) -> GameMode {
+        self.mode
+    }
+
+    pub fn rules(&self) -> Rules {
+        self.rules
+    }
+
+    pub fn players(&self) -> &Vec<String> {
+        &self.players
+    }
+
+    pub fn edition(&self) -> &Edition {
+        &self.edition
+    }
+
+    pub fn decks(&self) -> &Decks {
+        &self.decks
+    }
+
+    pub fn entries(&self) -> &Vec<JournalEntry> {
+        &self.entries
+    }
+
+    pub fn undone(&self) -> &Vec<JournalEntry> {
+        &self.undone
+    }
+
+    /// Append an executed command to the journal
+    // A new command means the undone commands can't be redone anymore
+    pub fn record(&mut self, command: GameCommand, events: Vec<GameEvent>) {
+        self.entries.push(JournalEntry { command, events });
+        self.undone.clear();
+    }
+
+    /// Take back the last entry
+    pub fn undo(&mut self) -> Option<JournalEntry> {
+        let entry = self.entries.pop()?;
+        self.undone.push(entry.clone());
+        Some(entry)
+    }
+
+    /// Restore the last entry that was taken back
+    pub fn redo(&mut self) -> Option<JournalEntry> {
+        let entry = self.undone.pop()?;
+        self.entries.push(entry.clone());
+        Some(entry)
+    }
+
+    /// Replay the journal on a new game
+    // Fails if the replayed game doesn't produce the same events as the journal
+    pub fn replay(&self) -> Result<Game, GameError> {
+        let mut g = game::init_with_edition(self.players.clone(), self.mode, self.rules,
+                                            self.seed, self.edition.clone(),
+                                            self.decks.clone())
+            .expect("Decks of the journal should fit its board");
+        for (i, entry) in self.entries.iter().enumerate() {
+            let events = g.apply(entry.command.clone());
+            if events != entry.events {
+                return Err(GameError::ReplayMismatch { entry: i + 1 });
+            }
+        }
+        Ok(g)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn replay_reproduces_game() {
+        let mut g = game::init(vec!["A".to_string(), "B".to_string()], GameMode::Classic,
+                               Rules::official());
+        g.apply(GameCommand::RollDice(1, 1)); // double
+        g.apply(GameCommand::RollDice(2, 3)); // Chance
+        g.apply(GameCommand::EndTurn);
+        g.apply(GameCommand::RollDice(1, 2)); // Baltic Avenue
+        g.apply(GameCommand::BuyProperty);
+        g.apply(GameCommand::EndTurn);
+        g.apply(GameCommand::RollDice(6, 5)); // Community chest, or Chance
+        g.apply(GameCommand::EndTurn);
+
+        let journal = g.journal();
+        let replayed = journal.replay().unwrap();
+        assert_eq!(replayed.save(), g.save());
+    }
+
+    #[test]
+    fn rejected_commands_are_not_recorded() {
+        let mut g = game::init(vec!["A".to_string(), "B".to_string()], GameMode::Classic,
+                               Rules::official());
+        g.apply(GameCommand::EndTurn);
+        g.apply(GameCommand::RollDice(1, 2));
+        let journal = g.journal();
+        assert_eq!(journal.entries().len(), 1);
+        assert_eq!(journal.entries()[0].command, GameCommand::RollDice(1, 2));
+    }
+
+    #[test]
+    fn replay_detects_changes() {
+        let mut g = game::init(vec!["A".to_string(), "B".to_string()], GameMode::Classic,
+                               Rules::official());
+        g.apply(GameCommand::RollDice(1, 2));
+        let mut journal = g.journal().clone();
+        journal.entries[0].events.clear();
+        assert_eq!(journal.replay().err(), Some(GameError::ReplayMismatch { entry: 1 }));
+    }
+
+    #[test]
+    fn undo_and_redo_entries() {
+        let mut journal = Journal::new(1, GameMode::Classic, Rules::official(),
+                                       vec!["A".to_string(), "B".to_string()],
+                                       Edition::us(), Decks::classic());
+        journal.record(GameCommand::RollDice(1, 2), Vec::new());
+        journal.record(GameCommand::BuyProperty, Vec::new());
+        assert_eq!(journal.undo().unwrap().command, GameCommand::BuyProperty);
+        assert_eq!(journal.undo().unwrap().command, GameCommand::RollDice(1, 2));
+        assert_eq!(journal.undo(), None);
+        assert_eq!(journal.redo().unwrap().command, GameCommand::RollDice(1, 2));
+        assert_eq!(journal.entries().len(), 1);
+        assert_eq!(journal.undone().len(), 1);
+
+        // a new command discards the undone commands
+        journal.record(GameCommand::EndTurn, Vec::new());
+        assert_eq!(journal.redo(), None);
+    }
+}
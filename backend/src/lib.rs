@@ -1,6 +1,16 @@
+pub mod auction;
 pub mod dialog;
 pub mod card;
+pub mod command;
+pub mod decision;
+pub mod edition;
+pub mod error;
+pub mod event;
 pub mod game;
+pub mod journal;
 pub mod player;
 pub mod publisher;
+pub mod rules;
+pub mod save;
 pub mod square;
+pub mod trade;
@@ -1,3 +1,9 @@
+use serde::{Deserialize, Serialize};
+
+use super::card::{Card, Deck};
+use super::error::GameError;
+
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
 pub struct Asset {
     owner: Option<usize>, // usize is a reference to a players turn_idx
     house_num: u32,
@@ -5,15 +11,41 @@ pub struct Asset {
     is_mortgaged: bool
 }
 
-#[derive(PartialEq, Eq, Hash, Debug)]
+/// Who a player in trouble owes money to
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug, Serialize, Deserialize)]
+pub enum Creditor {
+    Bank,
+    Player(usize) // turn_idx of the player
+}
+
+/// Why a player owes money
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug, Serialize, Deserialize)]
+pub enum DebtReason {
+    Rent { square: usize },
+    Tax { square: usize },
+    Card,
+    Bail, // after three turns in jail
+    Interest { square: usize } // on a mortgaged street, received from another player
+}
+
+/// Money a player still has to pay
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct Debt {
+    pub amount: u32,
+    pub creditor: Creditor,
+    pub reason: DebtReason
+}
+
+#[derive(PartialEq, Eq, Hash, Clone, Debug, Serialize, Deserialize)]
 pub struct Player {
     name: String,
     position: usize, // the index of the board square
     turn_idx: usize, // idx in the suburb of players. need this to match asset
     cash: u32,
     is_in_jail: bool,
-    num_get_out_of_jail_cards: u32,
-    is_in_trouble: bool, // true if player cannot pay bills, and needs to sell
+    jail_turns: u32, // turns served in jail, by rolling without a double
+    jail_cards: Vec<(Deck, Card)>, // get-out-of-jail-free cards, and the deck they return to
+    debts: Vec<Debt>, // bills the player couldn't pay yet, and needs to sell for
     left_game: bool // true if player has left the game
 }
 
@@ -55,33 +87,33 @@ impl Asset {
         self.has_hotel
     }
 
-    pub fn buy_house(&mut self) -> Result<(), String> {
+    pub fn buy_house(&mut self) -> Result<(), GameError> {
         match self.house_num < 4 {
             true => {
                 self.house_num += 1;
                 Ok(())
             },
-            false => Err(String::from("This street cannot have more houses"))
+            false => Err(GameError::MaxHouses)
         }
     }
 
-    pub fn sell_house(&mut self) -> Result<(), String> {
+    pub fn sell_house(&mut self) -> Result<(), GameError> {
         // validate street can sell house
         match self.house_num > 0 {
             true => { 
                 self.house_num -= 1;
                 Ok(())
             },
-            false => Err(String::from("This street has no houses"))
+            false => Err(GameError::NoHouses)
         }
     }
 
-    pub fn buy_hotel(&mut self) -> Result<(), String> {
+    pub fn buy_hotel(&mut self) -> Result<(), GameError> {
         if self.has_hotel() {
-            return Err(String::from("This street cannot have more hotels"));
+            return Err(GameError::HasHotel);
         }
         if self.house_num != 4 {
-            return Err(String::from("You need 4 houses before you can buy a hotel"));
+            return Err(GameError::HousesRequired);
         }
         self.has_hotel = true;
 
@@ -98,22 +130,24 @@ impl Asset {
         self.has_hotel() || self.house_num() > 0
     }
 
+    /// Return the street to the bank, without buildings or mortgage
     pub fn liquify(&mut self) {
-        self.owner = None;
+        *self = Asset::new();
     }
 }
 
 impl Player {
-    pub fn new(name: String, idx: usize) -> Self {
+    pub fn new(name: String, idx: usize, cash: u32) -> Self {
         Self {
             name,
             position: 0,
             turn_idx: idx,
-            cash: 1500, // 2x500, 4x100, 1x50, 1x20, 2x10, 1x5, 5x1
+            cash,
             is_in_jail: false,
-            is_in_trouble: false,
+            jail_turns: 0,
+            debts: Vec::new(),
             left_game: false,
-            num_get_out_of_jail_cards: 0,
+            jail_cards: Vec::new(),
         }
     }
 
@@ -145,23 +179,58 @@ impl Player {
     }
 
     pub fn is_in_trouble(&self) -> bool {
-        self.is_in_trouble
+        !self.debts.is_empty()
+    }
+
+    /// Outstanding debts, in the order they will be paid
+    pub fn debts(&self) -> &[Debt] {
+        &self.debts
+    }
+
+    /// Total amount the player still owes
+    pub fn debt_total(&self) -> u32 {
+        self.debts.iter().map(|d| d.amount).sum()
     }
     
+    pub fn jail_turns(&self) -> u32 {
+        self.jail_turns
+    }
+
     pub fn left_game(&self) -> bool {
         self.left_game
     }
 
     pub fn num_get_out_of_jail_cards(&self) -> u32 {
-        self.num_get_out_of_jail_cards
+        self.jail_cards.len() as u32
     }
 
-    pub fn set_in_trouble(&mut self, in_trouble: bool) {
-        self.is_in_trouble = in_trouble;
+    /// Record a bill the player couldn't pay
+    pub fn owe(&mut self, debt: Debt) {
+        self.debts.push(debt);
     }
-    
+
+    /// Pay off part of the first outstanding debt
+    pub fn reduce_debt(&mut self, amount: u32) {
+        if let Some(debt) = self.debts.first_mut() {
+            debt.amount -= std::cmp::min(amount, debt.amount);
+            if debt.amount == 0 {
+                self.debts.remove(0);
+            }
+        }
+    }
+
+    /// Owe the debts to another creditor, once the player they were owed to leaves
+    // Debts the player would owe to themselves are cancelled
+    pub fn transfer_debts(&mut self, from: Creditor, to: Creditor, own_idx: usize) {
+        for debt in self.debts.iter_mut().filter(|d| d.creditor == from) {
+            debt.creditor = to;
+        }
+        self.debts.retain(|d| d.creditor != Creditor::Player(own_idx));
+    }
+
     pub fn leave_game(&mut self) {
         self.left_game = true;
+        self.debts.clear();
     }
 
     /// Advance player
@@ -171,21 +240,29 @@ impl Player {
         self.position = target_square as usize;
     }
 
-    /// Go to jail
+    /// Move player back
+    // Going back from GO wraps around to the end of the board
+    pub fn go_back(&mut self, steps: u32, board_size: u32) {
+        let steps = steps % board_size;
+        let target_square = ((self.position as u32) + board_size - steps) % board_size;
+        self.position = target_square as usize;
+    }
+
+    /// Go to jail, at this board index
     // Player doesn't collect 200, and goes straight to jail
-    pub fn go_to_jail(&mut self) {
-        println!("GO TO JAIL");
+    pub fn go_to_jail(&mut self, jail: usize) {
         self.is_in_jail = true;
-        self.position = 10;
+        self.jail_turns = 0;
+        self.position = jail;
     }
 
     /// Transact in cash.
     // Adds `amount` to players cash amount. Also works for negative numbers
-    pub fn transact_cash(&mut self, amount: i32) -> Result<(), ()> {
+    pub fn transact_cash(&mut self, amount: i32) -> Result<(), GameError> {
         if amount < 0 {
             let a = amount.abs() as u32;
             if self.cash < a {
-                return Err(());
+                return Err(GameError::InsufficientFunds { needed: a, available: self.cash });
             }
             self.cash -= a;
         } else {
@@ -197,30 +274,40 @@ impl Player {
 
     pub fn leave_jail(&mut self) {
         self.is_in_jail = false;
+        self.jail_turns = 0;
     }
 
-    pub fn bribe_guards(&mut self) -> Result<(), ()> {
-        if self.cash < 50 {
-            println!("Oh no. You don't have enough cash to bribe the guards");
-            return Err(());
-        }
-        println!("Yay, No More Jail, since you bribed the guards $50");
-        self.transact_cash(-50);
-        self.is_in_jail = false;
-        Ok(())
+    /// Stay in jail for another turn, returning the number of turns served
+    pub fn serve_jail_turn(&mut self) -> u32 {
+        self.jail_turns += 1;
+        self.jail_turns
     }
 
-    pub fn redeem_jail_free_card(&mut self) -> Result<(), ()> {
-        if self.num_get_out_of_jail_cards < 1 {
-            return Err(());
-        }
-        self.num_get_out_of_jail_cards -= 1;
-        self.is_in_jail = false;
+    pub fn bribe_guards(&mut self, bail: u32) -> Result<(), GameError> {
+        self.transact_cash(-(bail as i32))?;
+        self.leave_jail();
         Ok(())
     }
 
-    pub fn receive_jail_free_card(&mut self) {
-        self.num_get_out_of_jail_cards += 1;
+    /// Leave jail with a get-out-of-jail-free card, returning the card for its deck
+    pub fn redeem_jail_free_card(&mut self) -> Result<(Deck, Card), GameError> {
+        let card = self.give_jail_free_card()?;
+        self.leave_jail();
+        Ok(card)
+    }
+
+    pub fn receive_jail_free_card(&mut self, deck: Deck, card: Card) {
+        self.jail_cards.push((deck, card));
+    }
+
+    /// Hand over a get-out-of-jail-free card, like in a trade
+    pub fn give_jail_free_card(&mut self) -> Result<(Deck, Card), GameError> {
+        self.jail_cards.pop().ok_or(GameError::NoJailCard)
+    }
+
+    /// Hand over all get-out-of-jail-free cards
+    pub fn give_up_jail_free_cards(&mut self) -> Vec<(Deck, Card)> {
+        std::mem::take(&mut self.jail_cards)
     }
 }
 
@@ -230,19 +317,38 @@ mod tests {
 
     #[test]
     fn advance_player() {
-        let ref mut p = Player::new("Test".to_string(), 1);
+        let ref mut p = Player::new("Test".to_string(), 1, 1500);
         assert_eq!(p.position, 0);
         p.advance(37, 40);
         assert_eq!(p.position, 37);
-        p.advance(5, 40); // wrap around BOARD_SIZE (=40)
+        p.advance(5, 40); // wrap around the board of 40 squares
         assert_eq!(p.position, 2);
+        p.go_back(3, 40);
+        assert_eq!(p.position, 39);
     }
 
     #[test]
     fn check_bankrupt() {
-        let mut p = Player::new("Test".to_string(), 1);
+        let mut p = Player::new("Test".to_string(), 1, 1500);
         assert_eq!(p.transact_cash(-500), Ok(()));
         assert_eq!(p.transact_cash(-1000), Ok(()));
-        assert_eq!(p.transact_cash(-1), Err(()));
+        assert_eq!(p.transact_cash(-1),
+                   Err(GameError::InsufficientFunds { needed: 1, available: 0 }));
+    }
+
+    #[test]
+    fn pay_off_debts() {
+        let mut p = Player::new("Test".to_string(), 0, 1500);
+        p.owe(Debt { amount: 100, creditor: Creditor::Bank, reason: DebtReason::Card });
+        p.owe(Debt { amount: 50, creditor: Creditor::Player(1),
+                     reason: DebtReason::Rent { square: 1 } });
+        assert_eq!(p.debt_total(), 150);
+        p.reduce_debt(60);
+        assert_eq!(p.debts()[0].amount, 40);
+        p.reduce_debt(40);
+        assert_eq!(p.debts().len(), 1);
+        assert_eq!(p.is_in_trouble(), true);
+        p.reduce_debt(50);
+        assert_eq!(p.is_in_trouble(), false);
     }
 }
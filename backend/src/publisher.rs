@@ -2,7 +2,7 @@ use std::fs;
 use async_process::Command;
 
 
-use super::game::{Game};
+use super::game::{Game, GameMode, JAIL_TURNS};
 use super::square::Square;
 
 const TEMP_FILE :&str = "/tmp/index.html";
@@ -11,8 +11,27 @@ const TEMP_FILE :&str = "/tmp/index.html";
 // Prints out stats for each player
 fn print_summary(game: &Game) {
     println!("==== Summary ====");
-    for p_ref in game.players().iter() {
-        let p = p_ref.borrow();
+    match game.mode() {
+        GameMode::Classic => {},
+        GameMode::TurnLimit(turns) => println!("⏳ Turn {} of {}", game.turn(), turns),
+        GameMode::TimeLimit(_) => {
+            let seconds = game.seconds_left().unwrap_or(0);
+            println!("⏳ {}:{:02} left", seconds / 60, seconds % 60);
+        }
+    };
+    println!("🏦 The bank has {} houses and {} hotels left", game.houses_left(),
+             game.hotels_left());
+    if game.rules().free_parking_jackpot {
+        println!("💰 The Free Parking jackpot is ${}", game.jackpot());
+    }
+    if let Some(winner) = game.winner() {
+        println!("🏆 {} wins the game", game.players()[winner].name());
+        for (i, s) in game.standings().iter().enumerate() {
+            println!("\t {}. {} is worth ${}", i + 1, game.players()[s.player].name(),
+                     s.net_worth);
+        }
+    }
+    for p in game.players().iter() {
         let board = game.board();
         let occupying_square = board.get(p.position())
             .expect("Player is not on the board");
@@ -22,16 +41,21 @@ fn print_summary(game: &Game) {
             continue;
         }
         match p.is_in_jail() {
-            true  => println!("\t is IN JAIL 🚧, but still has ${}", p.cash()),
+            true  => println!("\t is IN JAIL 🚧 ({} of {} turns served), but still has ${}",
+                              p.jail_turns(), JAIL_TURNS, p.cash()),
             false => println!("\t is on {} with ${}", occupying_square.name(), p.cash()) 
         };
+        println!("\t is worth ${}", game.net_worth(p.turn_idx()));
         if p.num_get_out_of_jail_cards() > 0 {
             println!("\t has {} get-out-of-jail cards", p.num_get_out_of_jail_cards());
         }
+        if p.is_in_trouble() {
+            println!("\t still owes ${}", p.debt_total());
+        }
         let board = game.board();
         let owned_streets = board.iter()
             .filter(|&x| {
-                match x.asset.borrow().owner() {
+                match x.asset().owner() {
                     None => false,
                     Some(owner_idx) => owner_idx == p.turn_idx()
                 }
@@ -43,7 +67,7 @@ fn print_summary(game: &Game) {
                 println!("\t owns {} assets:", owned_streets.len());
                 for s in owned_streets.iter() {
                     let mut x = s.name().to_string();
-                    let a = s.asset.borrow();
+                    let a = s.asset();
                     if a.is_mortgaged() {
                         x.push_str(" (mortgaged)");
                     } else if a.has_hotel() {
@@ -76,8 +100,19 @@ pub fn publish(game: &Game) {
     print_summary(game);
 
     let mut sb = String::from("<h1>Monopoly</h1>");
-    for p_ref in game.players().iter() {
-        let p = p_ref.borrow();
+    if game.rules().free_parking_jackpot {
+        sb.push_str(&format!("<p>💰 The Free Parking jackpot is ${}</p>", game.jackpot()));
+    }
+    if let Some(winner) = game.winner() {
+        sb.push_str(&format!("<h2>🏆 {} wins the game</h2><ol>",
+                             game.players()[winner].name()));
+        for s in game.standings().iter() {
+            sb.push_str(&format!("<li>{} is worth ${}</li>",
+                                 game.players()[s.player].name(), s.net_worth));
+        }
+        sb.push_str("</ol>");
+    }
+    for p in game.players().iter() {
         let board = game.board();
         let occupying_square = board.get(p.position())
             .expect("Player is not on the board");
@@ -99,7 +134,7 @@ pub fn publish(game: &Game) {
       }
         let board = game.board();
         let owned_streets = board.iter()
-            .filter(|&x| { match x.asset.borrow().owner() {
+            .filter(|&x| { match x.asset().owner() {
                 None => false,
                 Some(owner_idx) => owner_idx == p.turn_idx()
             }})
@@ -110,7 +145,7 @@ pub fn publish(game: &Game) {
                 sb.push_str(&format!("<li>owns: <ul>"));
                 for s in owned_streets.iter() {
                     let mut x = s.name().to_string();
-                    let a = s.asset.borrow();
+                    let a = s.asset();
                     if a.is_mortgaged() {
                         x.push_str(" (mortgaged)");
                     } else if a.has_hotel() {
@@ -0,0 +1,88 @@
+use serde::{Deserialize, Serialize};
+
+/// House rules the players agree on before the game starts
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub struct Rules {
+    pub starting_cash: u32,
+    pub go_salary: u32, // collected when passing GO
+    pub double_go_salary: bool, // landing exactly on GO pays the salary twice
+    pub no_rent_in_jail: bool, // owners don't collect rent while in jail
+    pub free_parking_jackpot: bool, // taxes and card payments go to whoever lands on it
+    pub income_tax_choice: bool, // pay a percentage of the net worth instead of the income tax
+    pub bail: u32 // paid to leave jail
+}
+
+impl Rules {
+    /// Rules of the official game
+    pub fn official() -> Self {
+        Self {
+            starting_cash: 1500,
+            go_salary: 200,
+            double_go_salary: false,
+            no_rent_in_jail: false,
+            free_parking_jackpot: false,
+            income_tax_choice: false,
+            bail: 50
+        }
+    }
+
+    /// Rules of the game before 2008, where income tax can also be paid as a
+    /// percentage of the net worth
+    pub fn classic() -> Self {
+        Self {
+            income_tax_choice: true,
+            ..Self::official()
+        }
+    }
+
+    /// Rules many families play with, giving out more money
+    pub fn family() -> Self {
+        Self {
+            double_go_salary: true,
+            no_rent_in_jail: true,
+            free_parking_jackpot: true,
+            ..Self::official()
+        }
+    }
+
+    /// Rules for a shorter game, where players can buy streets sooner
+    pub fn quick() -> Self {
+        Self {
+            starting_cash: 2500,
+            go_salary: 400,
+            bail: 100,
+            ..Self::official()
+        }
+    }
+
+    /// Preset by its name (eg family)
+    pub fn named(name: &str) -> Option<Self> {
+        match name {
+            "official" => Some(Self::official()),
+            "classic"  => Some(Self::classic()),
+            "family"   => Some(Self::family()),
+            "quick"    => Some(Self::quick()),
+            _          => None
+        }
+    }
+}
+
+impl Default for Rules {
+    fn default() -> Self {
+        Self::official()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn presets_by_name() {
+        assert_eq!(Rules::named("official"), Some(Rules::default()));
+        assert_eq!(Rules::named("family").unwrap().starting_cash, 1500);
+        assert!(Rules::named("classic").unwrap().income_tax_choice);
+        assert_eq!(Rules::named("quick").unwrap().go_salary, 400);
+        assert_eq!(Rules::named("monopoly"), None);
+    }
+}
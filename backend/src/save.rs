@@ -0,0 +1,129 @@
+use std::fs;
+
+use serde::{Deserialize, Serialize};
+
+use super::game::{Game, Dice, TaxChoice, TurnPhase};
+use super::{auction, card, journal, player, trade};
+
+/// Version of the saved game document. Increase it when the document changes after
+/// a release
+pub const VERSION: u32 = 1;
+
+/// File the command line interface saves the game to, after every command
+pub const AUTOSAVE_FILE: &str = "/tmp/monopoly.json";
+
+/// Complete state of a game, as saved to disk
+// The board itself isn't saved, only what happened on it: the assets are listed
+// in the order of the squares on the board.
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub struct SavedGame {
+    pub version: u32,
+    pub players: Vec<player::Player>,
+    pub assets: Vec<player::Asset>,
+    pub active_player: usize,
+    pub chance_cards: Vec<card::Card>, // in the order they will be drawn
+    pub community_cards: Vec<card::Card>,
+    pub phase: TurnPhase,
+    pub dice: Option<Dice>, // doubles rolled so far in this turn
+    pub auctions: Vec<usize>, // streets the bank still has to auction
+    pub resume_phase: TurnPhase, // phase of the turn once those auctions are over
+    pub bidding: Option<auction::Auction>, // bids on the street or house on auction
+    pub house_bid: Option<auction::Bid>, // winning bid on a house, not built yet
+    pub trade: Option<trade::Trade>, // waiting for an answer of the counterparty
+    pub mortgage_choices: Vec<usize>, // mortgaged streets the new owner may still lift
+    pub rent_roll: Option<u32>, // the active player rolls again for the rent of a utility
+    pub tax_choice: Option<TaxChoice>, // income tax the active player still chooses how to pay
+    pub jackpot: u32, // taxes and card payments waiting on Free Parking
+    pub turn: u32,
+    pub deadline: Option<u64>, // end of a timed game, in seconds since the Unix epoch
+    pub journal: journal::Journal
+}
+
+/// Save the game as a JSON document
+pub fn to_json(game: &Game) -> String {
+    serde_json::to_string_pretty(&game.save())
+        .expect("Game state can always be serialized")
+}
+
+/// Restore a game from a JSON document
+pub fn from_json(json: &str) -> Result<Game, String> {
+    let saved: SavedGame = match serde_json::from_str(json) {
+        Ok(s)  => s,
+        Err(e) => return Err(format!("Invalid saved game: {}", e))
+    };
+    if saved.version != VERSION {
+        return Err(format!("Saved game has version {}, but only version {} is supported",
+                           saved.version, VERSION));
+    }
+    Game::restore(saved)
+}
+
+/// Save the game to a file
+pub fn save_to_file(game: &Game, path: &str) -> Result<(), String> {
+    match fs::write(path, to_json(game)) {
+        Ok(_)  => Ok(()),
+        Err(e) => Err(format!("Cannot save the game to {}: {}", path, e))
+    }
+}
+
+/// Restore a game from a file
+pub fn load_from_file(path: &str) -> Result<Game, String> {
+    match fs::read_to_string(path) {
+        Ok(json) => from_json(&json),
+        Err(e)   => Err(format!("Cannot read the saved game {}: {}", path, e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::command::GameCommand;
+    use super::super::{card, game, rules};
+
+    #[test]
+    fn save_and_restore() {
+        let mut g = game::init(vec!["A".to_string(), "B".to_string()], game::GameMode::Classic,
+                           rules::Rules::official());
+        g.apply(GameCommand::RollDice(1, 2)); // Baltic Avenue
+        g.apply(GameCommand::BuyProperty);
+        g.apply(GameCommand::Mortgage { street: 3 });
+        g.apply(GameCommand::EndTurn);
+        g.apply(GameCommand::RollDice(3, 3)); // rolled a double
+
+        let json = to_json(&g);
+        let mut restored = from_json(&json).unwrap();
+        assert_eq!(restored.save(), g.save());
+        assert_eq!(to_json(&restored), json);
+        assert_eq!(restored.active_player(), 1);
+        assert_eq!(restored.players()[0].cash(), 1470);
+        assert_eq!(restored.board()[3].asset().owner(), Some(0));
+        assert_eq!(restored.board()[3].asset().is_mortgaged(), true);
+
+        // the restored game continues where it was saved
+        restored.apply(GameCommand::RollDice(1, 2));
+        assert_eq!(restored.players()[1].position(), 9);
+    }
+
+    #[test]
+    fn reject_invalid_saves() {
+        let g = game::init(vec!["A".to_string(), "B".to_string()], game::GameMode::Classic,
+                           rules::Rules::official());
+        let mut saved = g.save();
+        saved.version = VERSION + 1;
+        let json = serde_json::to_string(&saved).unwrap();
+        assert!(from_json(&json).is_err());
+
+        let mut saved = g.save();
+        saved.active_player = 2;
+        let json = serde_json::to_string(&saved).unwrap();
+        assert!(from_json(&json).is_err());
+
+        let mut saved = g.save();
+        saved.chance_cards.push(card::Card::new("Nowhere", card::CardAction::Movement,
+                                                None, Some(40)));
+        let json = serde_json::to_string(&saved).unwrap();
+        assert!(from_json(&json).is_err());
+
+        assert!(from_json("{\"version\": 2}").is_err());
+    }
+}
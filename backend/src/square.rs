@@ -1,15 +1,22 @@
-use std::cell::RefCell;
+use serde::{Deserialize, Serialize};
 
 use super::player::{Asset};
 
-#[derive(Eq, PartialEq, Debug, Clone, Copy)]
+#[derive(Eq, PartialEq, Hash, Debug, Clone, Copy, Serialize, Deserialize)]
 pub enum SquareType {
     ChanceCard,
     CommunityCard,
-    Corner,
+    Go,
+    Jail, // just visiting, or in jail
+    FreeParking,
+    GoToJail,
     Station,
     Street,
-    Tax,
+    Tax {
+        amount: u32,
+        #[serde(default)]
+        percent: Option<u32> // of the net worth, which can be paid instead
+    },
     Utility
 }
 
@@ -31,29 +38,12 @@ pub struct Square {
     name: String,
     square_type: SquareType,
     street_details: Option<StreetDetails>,
-    pub asset: RefCell<Asset>
-//        FIXME: remove pub of Asset
+    asset: Asset
 }
 
 impl StreetDetails {
-    pub fn new(color: char, price: u32, rent: u32, rent_suburb: [u32; 6],
+    pub fn new(suburb: Option<Suburb>, price: u32, rent: u32, rent_suburb: [u32; 6],
            mortgage: u32) -> Self {
-
-        // Create a suburb based on the street
-        // TODO: this can be more elegant, with the Street details referencing suburbs.
-
-        let suburb = match color {
-            'B' => Some(Suburb::new("Brown", 50)),
-            'L' => Some(Suburb::new("Blue", 50)),
-            'P' => Some(Suburb::new("Pink", 100)), 
-            'O' => Some(Suburb::new("Orange", 100)),
-            'R' => Some(Suburb::new("Red", 150)),
-            'Y' => Some(Suburb::new("Yellow", 150)),
-            'G' => Some(Suburb::new("Green", 200)),
-            'I' => Some(Suburb::new("Indigo", 200)),
-            _   => None
-        };
-
         Self {
             suburb,
             price,
@@ -86,7 +76,7 @@ impl StreetDetails {
 }
 
 impl Suburb {
-    fn new(color: &str, building_price: u32) -> Self {
+    pub fn new(color: &str, building_price: u32) -> Self {
         Self {
             color: color.to_string(),
             building_price
@@ -113,7 +103,7 @@ impl Square {
             name: name.to_string(),
             square_type,
             street_details: details,
-            asset: RefCell::new(Asset::new())
+            asset: Asset::new()
         }
     }
 
@@ -125,18 +115,24 @@ impl Square {
         self.name.as_ref()
     }
 
+    /// Ownership, buildings and mortgage of the square
+    pub fn asset(&self) -> &Asset {
+        &self.asset
+    }
+
+    pub fn asset_mut(&mut self) -> &mut Asset {
+        &mut self.asset
+    }
+
     pub fn square_type(&self) -> SquareType {
         self.square_type
     }
 
     /// Get purchase price of the street
     pub fn get_price(&self) -> u32 {
-        match self.square_type {
-            SquareType::Station => 200,
-            SquareType::Utility => 150,
-            SquareType::Street  => self.get_street_details()
-                    .expect("Details should exist").price,
-                _ => 0 // Error, should never happen
+        match self.get_street_details() {
+            Some(details) => details.price,
+            None          => 0 // Error, should never happen
         }
     }
 }
@@ -0,0 +1,60 @@
+use serde::{Deserialize, Serialize};
+
+/// Everything one side of a trade hands over
+#[derive(Clone, PartialEq, Eq, Debug, Default, Serialize, Deserialize)]
+pub struct TradeOffer {
+    pub streets: Vec<usize>, // board index of the streets
+    pub cash: u32,
+    pub jail_cards: u32
+}
+
+/// A deal one player proposes to another
+// The proposer hands over `gives`, and receives `receives` from the counterparty.
+// Nothing changes hands until the counterparty accepts.
+#[derive(Clone, PartialEq, Eq, Debug, Serialize, Deserialize)]
+pub struct Trade {
+    pub proposer: usize, // turn_idx of the players
+    pub counterparty: usize,
+    pub gives: TradeOffer,
+    pub receives: TradeOffer
+}
+
+impl TradeOffer {
+    pub fn is_empty(&self) -> bool {
+        self.streets.is_empty() && self.cash == 0 && self.jail_cards == 0
+    }
+}
+
+impl Trade {
+    /// The counterparty proposes a different deal instead
+    // Gives and receives are seen from the counterparty, who becomes the proposer
+    pub fn counter(&self, gives: TradeOffer, receives: TradeOffer) -> Trade {
+        Trade {
+            proposer: self.counterparty,
+            counterparty: self.proposer,
+            gives,
+            receives
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counter_swaps_players() {
+        let trade = Trade {
+            proposer: 0,
+            counterparty: 1,
+            gives: TradeOffer { streets: vec![1], cash: 0, jail_cards: 0 },
+            receives: TradeOffer { streets: Vec::new(), cash: 100, jail_cards: 0 }
+        };
+        let counter = trade.counter(TradeOffer { cash: 150, ..Default::default() },
+                                    TradeOffer { streets: vec![1], ..Default::default() });
+        assert_eq!((counter.proposer, counter.counterparty), (1, 0));
+        assert_eq!(counter.gives.cash, 150);
+        assert_eq!(counter.receives.streets, vec![1]);
+        assert!(TradeOffer::default().is_empty());
+    }
+}